@@ -1,10 +1,77 @@
-use criterion::{criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use sirena::signal::{self, Signal, SignalAddAmp, SignalMulAmp};
+use sirena::state_variable_filter::{LowPass, StateVariableFilter};
 
-fn criterion_benchmark(c: &mut Criterion) {
-    c.bench_function("placeholder", |b| {
-        b.iter(|| {});
+const BLOCK_SIZE: usize = 64;
+
+fn signal_block(c: &mut Criterion) {
+    let mut group = c.benchmark_group("sine_mul_add");
+
+    let chain = || {
+        signal::sine(48000.0, 440.0)
+            .mul_amp(signal::constant(0.5))
+            .add_amp(signal::constant(0.1))
+    };
+
+    let mut signal = chain();
+    group.bench_function("next", |b| {
+        let mut buffer = [0.0; BLOCK_SIZE];
+        b.iter(|| {
+            for x in buffer.iter_mut() {
+                *x = signal.next();
+            }
+            black_box(&buffer);
+        });
+    });
+
+    let mut signal = chain();
+    group.bench_function("fill", |b| {
+        let mut buffer = [0.0; BLOCK_SIZE];
+        b.iter(|| {
+            signal.fill(&mut buffer);
+            black_box(&buffer);
+        });
+    });
+
+    group.finish();
+}
+
+fn svf_modulation(c: &mut Criterion) {
+    let mut group = c.benchmark_group("svf_modulation");
+
+    let mut input = [0.0; BLOCK_SIZE];
+    signal::white_noise(1).fill(&mut input);
+    let mut frequency = [0.0; BLOCK_SIZE];
+    signal::sine(48000.0, 1.0).fill(&mut frequency);
+    frequency.iter_mut().for_each(|x| *x = 1000.0 + *x * 500.0);
+
+    let mut filter = StateVariableFilter::new(48000);
+    filter.set_bandform(LowPass);
+    group.bench_function("set_frequency", |b| {
+        let mut buffer = [0.0; BLOCK_SIZE];
+        b.iter(|| {
+            for (i, x) in buffer.iter_mut().enumerate() {
+                filter.set_frequency(frequency[i]);
+                *x = filter.tick(input[i]);
+            }
+            black_box(&buffer);
+        });
     });
+
+    let mut filter = StateVariableFilter::new(48000);
+    filter.set_bandform(LowPass);
+    group.bench_function("tick_modulated", |b| {
+        let mut buffer = [0.0; BLOCK_SIZE];
+        b.iter(|| {
+            for (i, x) in buffer.iter_mut().enumerate() {
+                *x = filter.tick_modulated(input[i], frequency[i], 0.7);
+            }
+            black_box(&buffer);
+        });
+    });
+
+    group.finish();
 }
 
-criterion_group!(benches, criterion_benchmark);
+criterion_group!(benches, signal_block, svf_modulation);
 criterion_main!(benches);
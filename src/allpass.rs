@@ -0,0 +1,112 @@
+//! Schroeder all-pass filter built on top of a ring buffer.
+//!
+//! The filter passes all frequencies with unchanged magnitude while
+//! smearing the signal in time. Chained all-pass filters are used to
+//! diffuse echoes in reverbs.
+
+use crate::comb::clamp_delay;
+use crate::ring_buffer::RingBuffer;
+
+/// Yields all-pass filtered signal.
+///
+/// Size of the buffer `N` limits the longest delay in samples.
+pub struct AllPass<const N: usize> {
+    sample_rate: u32,
+    buffer: RingBuffer<N>,
+    delay: f32,
+    feedback: f32,
+}
+
+impl<const N: usize> AllPass<N> {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            buffer: RingBuffer::new(),
+            delay: 1.0,
+            feedback: 0.0,
+        }
+    }
+
+    /// Set delay time in seconds. It can be fractional, although the linear
+    /// interpolation slightly attenuates high frequencies then.
+    ///
+    /// It gets clamped between one sample and the size of the buffer.
+    pub fn set_delay(&mut self, time: f32) -> &mut Self {
+        self.delay = clamp_delay::<N>(time * self.sample_rate as f32);
+        self
+    }
+
+    /// Set amount of delayed signal fed back, controlling the density of
+    /// the diffusion. Keep it between -1.0 and 1.0 for the filter to remain
+    /// stable.
+    pub fn set_feedback(&mut self, feedback: f32) -> &mut Self {
+        self.feedback = feedback;
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let delayed = self.buffer.peek_interpolated(1.0 - self.delay);
+        let v = value + delayed * self.feedback;
+        self.buffer.write(v);
+        delayed - v * self.feedback
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+
+    const SAMPLE_RATE: u32 = 1024;
+
+    #[test]
+    fn initialize_filter() {
+        const SAMPLE_RATE: u32 = 1;
+        let _filter = AllPass::<8>::new(SAMPLE_RATE);
+    }
+
+    #[test]
+    fn keep_magnitude() {
+        for feedback in [-0.7, 0.3, 0.7] {
+            let mut filter = AllPass::<32>::new(SAMPLE_RATE);
+            filter
+                .set_delay(13.0 / SAMPLE_RATE as f32)
+                .set_feedback(feedback);
+
+            let mut signal = [0.0; 1024];
+            signal[0] = 1.0;
+            filter.process(&mut signal);
+
+            let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+            for frequency in [0.0, 10.0, 100.0, 250.0, 400.0, 511.0] {
+                assert_relative_eq!(analysis.magnitude(frequency), 1.0, epsilon = 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn smear_impulse_in_time() {
+        let mut filter = AllPass::<16>::new(SAMPLE_RATE);
+        filter.set_delay(4.0 / SAMPLE_RATE as f32).set_feedback(0.5);
+
+        let mut signal = [0.0; 16];
+        signal[0] = 1.0;
+        filter.process(&mut signal);
+
+        assert_relative_eq!(signal[0], -0.5);
+        assert_relative_eq!(signal[4], 0.75);
+        assert_relative_eq!(signal[8], 0.375);
+    }
+}
@@ -0,0 +1,412 @@
+//! Biquad filter implementing responses of the Audio EQ Cookbook.
+//!
+//! Coefficients are calculated following Robert Bristow-Johnson's formulas.
+//! The filter can run in Direct Form I, which is robust against coefficient
+//! changes, or Transposed Direct Form II, which needs less state and has
+//! better numerical properties with floating point.
+
+use core::f32::consts::PI;
+
+/// Yields filtered signal.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct Biquad {
+    sample_rate: u32,
+    bandform: Bandform,
+    form: Form,
+    frequency: f32,
+    q_factor: f32,
+    gain: f32,
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+    x1: f32,
+    x2: f32,
+    y1: f32,
+    y2: f32,
+}
+
+impl Biquad {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut filter = Self {
+            sample_rate,
+            bandform: LowPass,
+            form: TransposedDirectForm2,
+            frequency: 0.0,
+            q_factor: 0.7,
+            gain: 0.0,
+            b0: 0.0,
+            b1: 0.0,
+            b2: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            x1: 0.0,
+            x2: 0.0,
+            y1: 0.0,
+            y2: 0.0,
+        };
+        filter.update_coefficients();
+        filter
+    }
+
+    pub fn set_bandform(&mut self, bandform: Bandform) -> &mut Self {
+        self.bandform = bandform;
+        self.update_coefficients();
+        self
+    }
+
+    /// Select the filter structure. This resets the state of the filter.
+    pub fn set_form(&mut self, form: Form) -> &mut Self {
+        self.form = form;
+        self.x1 = 0.0;
+        self.x2 = 0.0;
+        self.y1 = 0.0;
+        self.y2 = 0.0;
+        self
+    }
+
+    /// Set the cutoff or center frequency.
+    ///
+    /// It gets clamped just below the Nyquist frequency.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        let nyquist = self.sample_rate as f32 / 2.0;
+        self.frequency = frequency.clamp(0.0, nyquist * 0.999);
+        self.update_coefficients();
+        self
+    }
+
+    pub fn set_q_factor(&mut self, q_factor: f32) -> &mut Self {
+        self.q_factor = f32::max(q_factor, 0.01);
+        self.update_coefficients();
+        self
+    }
+
+    /// Set gain in decibels. It is used only by peaking and shelf filters.
+    pub fn set_gain(&mut self, gain: f32) -> &mut Self {
+        self.gain = gain;
+        self.update_coefficients();
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        match self.form {
+            DirectForm1 => {
+                let y = self.b0 * value + self.b1 * self.x1 + self.b2 * self.x2
+                    - self.a1 * self.y1
+                    - self.a2 * self.y2;
+                self.x2 = self.x1;
+                self.x1 = value;
+                self.y2 = self.y1;
+                self.y1 = y;
+                y
+            }
+            // The two delays of the transposed form are kept in x1 and x2.
+            TransposedDirectForm2 => {
+                let y = self.b0 * value + self.x1;
+                self.x1 = self.b1 * value - self.a1 * y + self.x2;
+                self.x2 = self.b2 * value - self.a2 * y;
+                y
+            }
+        }
+    }
+
+    // https://www.w3.org/TR/audio-eq-cookbook/
+    fn update_coefficients(&mut self) {
+        let w0 = 2.0 * PI * self.frequency / self.sample_rate as f32;
+        let cos = libm::cosf(w0);
+        let alpha = libm::sinf(w0) / (2.0 * self.q_factor);
+        let a = libm::powf(10.0, self.gain / 40.0);
+
+        let (b0, b1, b2, a0, a1, a2) = match self.bandform {
+            LowPass => (
+                (1.0 - cos) / 2.0,
+                1.0 - cos,
+                (1.0 - cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            HighPass => (
+                (1.0 + cos) / 2.0,
+                -(1.0 + cos),
+                (1.0 + cos) / 2.0,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            BandPass => (alpha, 0.0, -alpha, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            BandReject => (1.0, -2.0 * cos, 1.0, 1.0 + alpha, -2.0 * cos, 1.0 - alpha),
+            AllPass => (
+                1.0 - alpha,
+                -2.0 * cos,
+                1.0 + alpha,
+                1.0 + alpha,
+                -2.0 * cos,
+                1.0 - alpha,
+            ),
+            Peaking => (
+                1.0 + alpha * a,
+                -2.0 * cos,
+                1.0 - alpha * a,
+                1.0 + alpha / a,
+                -2.0 * cos,
+                1.0 - alpha / a,
+            ),
+            LowShelf => {
+                let shelf = 2.0 * libm::sqrtf(a) * alpha;
+                (
+                    a * ((a + 1.0) - (a - 1.0) * cos + shelf),
+                    2.0 * a * ((a - 1.0) - (a + 1.0) * cos),
+                    a * ((a + 1.0) - (a - 1.0) * cos - shelf),
+                    (a + 1.0) + (a - 1.0) * cos + shelf,
+                    -2.0 * ((a - 1.0) + (a + 1.0) * cos),
+                    (a + 1.0) + (a - 1.0) * cos - shelf,
+                )
+            }
+            HighShelf => {
+                let shelf = 2.0 * libm::sqrtf(a) * alpha;
+                (
+                    a * ((a + 1.0) + (a - 1.0) * cos + shelf),
+                    -2.0 * a * ((a - 1.0) + (a + 1.0) * cos),
+                    a * ((a + 1.0) + (a - 1.0) * cos - shelf),
+                    (a + 1.0) - (a - 1.0) * cos + shelf,
+                    2.0 * ((a - 1.0) - (a + 1.0) * cos),
+                    (a + 1.0) - (a - 1.0) * cos - shelf,
+                )
+            }
+        };
+
+        self.b0 = b0 / a0;
+        self.b1 = b1 / a0;
+        self.b2 = b2 / a0;
+        self.a1 = a1 / a0;
+        self.a2 = a2 / a0;
+    }
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Bandform {
+    LowPass,
+    HighPass,
+    /// Band pass with constant 0 dB peak gain.
+    BandPass,
+    BandReject,
+    AllPass,
+    /// Boosts or cuts frequencies around the center by set gain.
+    Peaking,
+    /// Boosts or cuts frequencies below the cutoff by set gain.
+    LowShelf,
+    /// Boosts or cuts frequencies above the cutoff by set gain.
+    HighShelf,
+}
+
+pub use Bandform::*;
+
+/// Structure of the filter.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Form {
+    DirectForm1,
+    TransposedDirectForm2,
+}
+
+pub use Form::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+    use crate::test_utils::{impulse_response, noise};
+    use core::f32::consts::FRAC_1_SQRT_2;
+
+    const SAMPLE_RATE: u32 = 1024;
+    const FORMS: [Form; 2] = [DirectForm1, TransposedDirectForm2];
+
+    fn power(decibels: f32) -> f32 {
+        libm::powf(10.0, decibels / 10.0)
+    }
+
+    #[test]
+    fn initialize_filter() {
+        const SAMPLE_RATE: u32 = 1;
+        let _filter = Biquad::new(SAMPLE_RATE);
+    }
+
+    #[test]
+    fn low_pass() {
+        for form in FORMS {
+            let mut signal = noise();
+
+            let mut filter = Biquad::new(SAMPLE_RATE);
+            filter
+                .set_form(form)
+                .set_bandform(LowPass)
+                .set_frequency(100.0);
+            filter.process(&mut signal);
+
+            let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+            let low_mean_magnitude = analysis.mean_magnitude(0.0, 100.0);
+            let high_mean_magnitude = analysis.mean_magnitude(100.0, 600.0);
+
+            assert!(low_mean_magnitude / high_mean_magnitude > 3.0);
+        }
+    }
+
+    #[test]
+    fn high_pass() {
+        for form in FORMS {
+            let mut signal = noise();
+
+            let mut filter = Biquad::new(SAMPLE_RATE);
+            filter
+                .set_form(form)
+                .set_bandform(HighPass)
+                .set_frequency(100.0);
+            filter.process(&mut signal);
+
+            let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+            let low_mean_magnitude = analysis.mean_magnitude(0.0, 100.0);
+            let high_mean_magnitude = analysis.mean_magnitude(100.0, 600.0);
+
+            assert!(high_mean_magnitude / low_mean_magnitude > 3.0);
+        }
+    }
+
+    #[test]
+    fn low_and_high_pass_attenuate_by_3_db_on_cutoff() {
+        for bandform in [LowPass, HighPass] {
+            for frequency in [20.0, 100.0, 300.0, 500.0] {
+                let mut filter = Biquad::new(SAMPLE_RATE);
+                filter
+                    .set_bandform(bandform)
+                    .set_frequency(frequency)
+                    .set_q_factor(FRAC_1_SQRT_2);
+                let analysis = impulse_response(|signal| filter.process(signal));
+
+                assert_relative_eq!(analysis.magnitude(frequency), 0.5, epsilon = 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn band_pass() {
+        let mut filter = Biquad::new(SAMPLE_RATE);
+        filter
+            .set_bandform(BandPass)
+            .set_frequency(300.0)
+            .set_q_factor(10.0);
+        let analysis = impulse_response(|signal| filter.process(signal));
+
+        assert_relative_eq!(analysis.strongest_peak(), 300.0, epsilon = 1.0);
+        assert_relative_eq!(analysis.magnitude(300.0), 1.0, epsilon = 0.01);
+        assert!(analysis.magnitude(100.0) < 0.01);
+    }
+
+    #[test]
+    fn band_reject() {
+        let mut filter = Biquad::new(SAMPLE_RATE);
+        filter
+            .set_bandform(BandReject)
+            .set_frequency(300.0)
+            .set_q_factor(1.0);
+        let analysis = impulse_response(|signal| filter.process(signal));
+
+        assert!(analysis.magnitude(300.0) < 0.01);
+        assert_relative_eq!(analysis.magnitude(50.0), 1.0, epsilon = 0.05);
+    }
+
+    #[test]
+    fn all_pass_keeps_magnitude() {
+        let mut filter = Biquad::new(SAMPLE_RATE);
+        filter
+            .set_bandform(AllPass)
+            .set_frequency(300.0)
+            .set_q_factor(2.0);
+        let analysis = impulse_response(|signal| filter.process(signal));
+
+        for frequency in [10.0, 100.0, 300.0, 500.0] {
+            assert_relative_eq!(analysis.magnitude(frequency), 1.0, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn peaking_applies_gain_on_center_frequency() {
+        for gain in [-6.0, 6.0] {
+            let mut filter = Biquad::new(SAMPLE_RATE);
+            filter
+                .set_bandform(Peaking)
+                .set_frequency(300.0)
+                .set_q_factor(2.0)
+                .set_gain(gain);
+            let analysis = impulse_response(|signal| filter.process(signal));
+
+            assert_relative_eq!(analysis.magnitude(300.0), power(gain), epsilon = 0.05);
+            assert_relative_eq!(analysis.magnitude(10.0), 1.0, epsilon = 0.05);
+        }
+    }
+
+    #[test]
+    fn low_shelf_applies_gain_below_cutoff() {
+        let mut filter = Biquad::new(SAMPLE_RATE);
+        filter
+            .set_bandform(LowShelf)
+            .set_frequency(100.0)
+            .set_gain(6.0);
+        let analysis = impulse_response(|signal| filter.process(signal));
+
+        assert_relative_eq!(analysis.magnitude(0.0), power(6.0), epsilon = 0.05);
+        assert_relative_eq!(analysis.magnitude(500.0), 1.0, epsilon = 0.05);
+    }
+
+    #[test]
+    fn high_shelf_applies_gain_above_cutoff() {
+        let mut filter = Biquad::new(SAMPLE_RATE);
+        filter
+            .set_bandform(HighShelf)
+            .set_frequency(100.0)
+            .set_gain(-6.0);
+        let analysis = impulse_response(|signal| filter.process(signal));
+
+        assert_relative_eq!(analysis.magnitude(0.0), 1.0, epsilon = 0.05);
+        assert_relative_eq!(analysis.magnitude(500.0), power(-6.0), epsilon = 0.05);
+    }
+
+    #[test]
+    fn both_forms_yield_equal_output() {
+        let signal = noise();
+
+        let mut direct = Biquad::new(SAMPLE_RATE);
+        direct
+            .set_form(DirectForm1)
+            .set_bandform(Peaking)
+            .set_frequency(200.0)
+            .set_q_factor(3.0)
+            .set_gain(12.0);
+        let mut transposed = Biquad::new(SAMPLE_RATE);
+        transposed
+            .set_form(TransposedDirectForm2)
+            .set_bandform(Peaking)
+            .set_frequency(200.0)
+            .set_q_factor(3.0)
+            .set_gain(12.0);
+
+        for x in signal.iter() {
+            assert_relative_eq!(direct.tick(*x), transposed.tick(*x), epsilon = 0.0001);
+        }
+    }
+}
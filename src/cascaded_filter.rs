@@ -0,0 +1,283 @@
+//! Cascade of state variable filters with selectable slope.
+//!
+//! Up to four [`ZdfStateVariableFilter`] stages are chained to achieve slopes
+//! from 12 to 48 dB per octave. Q factors of individual stages are set to
+//! match the selected alignment, so the combined response is maximally flat
+//! (Butterworth) or sums flat with its complement in a crossover
+//! (Linkwitz-Riley).
+
+use core::f32::consts::FRAC_1_SQRT_2;
+
+use crate::zdf_state_variable_filter::ZdfStateVariableFilter;
+
+pub use crate::state_variable_filter::Bandform::{self, *};
+
+// Q factors of second-order sections of Butterworth filters of order 2, 4, 6
+// and 8. The sections have poles placed evenly on a unit half-circle.
+const BUTTERWORTH_Q: [[f32; 4]; 4] = [
+    [FRAC_1_SQRT_2, 0.0, 0.0, 0.0],
+    [0.541_196_1, 1.306_563, 0.0, 0.0],
+    [0.517_638_1, FRAC_1_SQRT_2, 1.931_851_6, 0.0],
+    [0.509_795_6, 0.601_344_9, 0.899_976_2, 2.562_915_4],
+];
+
+// Linkwitz-Riley filter of order 2N is a Butterworth filter of order N
+// applied twice. Two first-order sections combine into a section with Q 0.5.
+const LINKWITZ_RILEY_Q: [[f32; 4]; 4] = [
+    [0.5, 0.0, 0.0, 0.0],
+    [FRAC_1_SQRT_2, FRAC_1_SQRT_2, 0.0, 0.0],
+    [0.5, 1.0, 1.0, 0.0],
+    [0.541_196_1, 0.541_196_1, 1.306_563, 1.306_563],
+];
+
+/// Yields filtered signal.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct CascadedFilter {
+    stages: [ZdfStateVariableFilter; 4],
+    slope: Slope,
+    alignment: Alignment,
+}
+
+impl CascadedFilter {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut filter = Self {
+            stages: [
+                ZdfStateVariableFilter::new(sample_rate),
+                ZdfStateVariableFilter::new(sample_rate),
+                ZdfStateVariableFilter::new(sample_rate),
+                ZdfStateVariableFilter::new(sample_rate),
+            ],
+            slope: Db24,
+            alignment: Butterworth,
+        };
+        filter.set_bandform(LowPass);
+        filter.update_q_factors();
+        filter
+    }
+
+    /// Set bandform of all stages.
+    ///
+    /// Low and high pass keep the selected alignment. Band pass and band
+    /// reject are supported, but their stages are not tuned to any alignment.
+    pub fn set_bandform(&mut self, bandform: Bandform) -> &mut Self {
+        for stage in self.stages.iter_mut() {
+            stage.set_bandform(bandform);
+        }
+        self
+    }
+
+    /// Set the cutoff frequency shared by all stages.
+    ///
+    /// Butterworth alignment attenuates by 3 dB on the cutoff, Linkwitz-Riley
+    /// by 6 dB.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        for stage in self.stages.iter_mut() {
+            stage.set_frequency(frequency);
+        }
+        self
+    }
+
+    pub fn set_slope(&mut self, slope: Slope) -> &mut Self {
+        self.slope = slope;
+        self.update_q_factors();
+        self
+    }
+
+    pub fn set_alignment(&mut self, alignment: Alignment) -> &mut Self {
+        self.alignment = alignment;
+        self.update_q_factors();
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let stages = self.slope.stages();
+        self.stages[..stages]
+            .iter_mut()
+            .fold(value, |value, stage| stage.tick(value))
+    }
+
+    fn update_q_factors(&mut self) {
+        let table = match self.alignment {
+            Butterworth => &BUTTERWORTH_Q,
+            LinkwitzRiley => &LINKWITZ_RILEY_Q,
+        };
+        let q_factors = &table[self.slope.stages() - 1];
+        for (stage, q_factor) in self.stages.iter_mut().zip(q_factors) {
+            stage.set_q_factor(*q_factor);
+        }
+    }
+}
+
+/// Steepness of the filter in decibels per octave.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Slope {
+    Db12,
+    Db24,
+    Db36,
+    Db48,
+}
+
+pub use Slope::*;
+
+impl Slope {
+    fn stages(self) -> usize {
+        match self {
+            Db12 => 1,
+            Db24 => 2,
+            Db36 => 3,
+            Db48 => 4,
+        }
+    }
+}
+
+/// Tuning of Q factors of individual stages.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Alignment {
+    /// Maximally flat pass band, attenuated by 3 dB on the cutoff.
+    Butterworth,
+    /// Attenuated by 6 dB on the cutoff, so low and high pass of the same
+    /// slope sum to a flat magnitude response. With 12 and 36 dB slopes, the
+    /// high pass must be inverted before summing.
+    LinkwitzRiley,
+}
+
+pub use Alignment::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+    use crate::test_utils::{impulse, impulse_response};
+
+    const SAMPLE_RATE: u32 = 1024;
+    const SLOPES: [Slope; 4] = [Db12, Db24, Db36, Db48];
+
+    fn decibels(magnitude: f32) -> f32 {
+        10.0 * libm::log10f(magnitude)
+    }
+
+    #[test]
+    fn initialize_filter() {
+        const SAMPLE_RATE: u32 = 1;
+        let _filter = CascadedFilter::new(SAMPLE_RATE);
+    }
+
+    #[test]
+    fn butterworth_low_pass_attenuates_by_3_db_on_cutoff() {
+        for slope in SLOPES {
+            let mut filter = CascadedFilter::new(SAMPLE_RATE);
+            filter
+                .set_bandform(LowPass)
+                .set_slope(slope)
+                .set_alignment(Butterworth)
+                .set_frequency(100.0);
+            let analysis = impulse_response(|signal| filter.process(signal));
+
+            assert_relative_eq!(analysis.magnitude(0.0), 1.0, epsilon = 0.01);
+            assert_relative_eq!(analysis.magnitude(20.0), 1.0, epsilon = 0.05);
+            assert_relative_eq!(analysis.magnitude(100.0), 0.5, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn butterworth_high_pass_attenuates_by_3_db_on_cutoff() {
+        for slope in SLOPES {
+            let mut filter = CascadedFilter::new(SAMPLE_RATE);
+            filter
+                .set_bandform(HighPass)
+                .set_slope(slope)
+                .set_alignment(Butterworth)
+                .set_frequency(100.0);
+            let analysis = impulse_response(|signal| filter.process(signal));
+
+            assert_relative_eq!(analysis.magnitude(0.0), 0.0, epsilon = 0.01);
+            assert_relative_eq!(analysis.magnitude(400.0), 1.0, epsilon = 0.05);
+            assert_relative_eq!(analysis.magnitude(100.0), 0.5, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn linkwitz_riley_attenuates_by_6_db_on_cutoff() {
+        for slope in SLOPES {
+            for bandform in [LowPass, HighPass] {
+                let mut filter = CascadedFilter::new(SAMPLE_RATE);
+                filter
+                    .set_bandform(bandform)
+                    .set_slope(slope)
+                    .set_alignment(LinkwitzRiley)
+                    .set_frequency(100.0);
+                let analysis = impulse_response(|signal| filter.process(signal));
+
+                assert_relative_eq!(analysis.magnitude(100.0), 0.25, epsilon = 0.01);
+            }
+        }
+    }
+
+    #[test]
+    fn linkwitz_riley_low_and_high_pass_sum_flat() {
+        for slope in SLOPES {
+            let mut low_pass = CascadedFilter::new(SAMPLE_RATE);
+            low_pass
+                .set_bandform(LowPass)
+                .set_slope(slope)
+                .set_alignment(LinkwitzRiley)
+                .set_frequency(100.0);
+            let mut high_pass = CascadedFilter::new(SAMPLE_RATE);
+            high_pass
+                .set_bandform(HighPass)
+                .set_slope(slope)
+                .set_alignment(LinkwitzRiley)
+                .set_frequency(100.0);
+
+            let mut signal = impulse();
+            for x in signal.iter_mut() {
+                let low = low_pass.tick(*x);
+                let high = high_pass.tick(*x);
+                *x = match slope {
+                    Db12 | Db36 => low - high,
+                    Db24 | Db48 => low + high,
+                };
+            }
+            let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+
+            for frequency in [10.0, 50.0, 100.0, 200.0, 400.0] {
+                assert_relative_eq!(analysis.magnitude(frequency), 1.0, epsilon = 0.02);
+            }
+        }
+    }
+
+    #[test]
+    fn steeper_slope_attenuates_more_an_octave_above_cutoff() {
+        let mut attenuations = [0.0; 4];
+        for (i, slope) in SLOPES.iter().enumerate() {
+            let mut filter = CascadedFilter::new(SAMPLE_RATE);
+            filter
+                .set_bandform(LowPass)
+                .set_slope(*slope)
+                .set_alignment(Butterworth)
+                .set_frequency(50.0);
+            let analysis = impulse_response(|signal| filter.process(signal));
+            attenuations[i] = decibels(analysis.magnitude(100.0));
+        }
+
+        for (i, attenuation) in attenuations.iter().enumerate() {
+            let expected = -12.0 * (i + 1) as f32;
+            assert!(*attenuation < expected + 1.0);
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Comb filters built on top of a ring buffer.
+//!
+//! Comb filters mix the signal with its delayed copy, which boosts and
+//! attenuates evenly spaced frequencies. Parallel feedback combs form the
+//! body of Schroeder reverbs, and a single damped feedback comb excited by
+//! a burst of noise is the core of the Karplus-Strong string synthesis.
+
+use crate::ring_buffer::RingBuffer;
+
+/// Comb filter feeding its delayed output back to the input.
+///
+/// Size of the buffer `N` limits the longest delay in samples.
+pub struct FeedbackComb<const N: usize> {
+    sample_rate: u32,
+    buffer: RingBuffer<N>,
+    delay: f32,
+    feedback: f32,
+    damping: f32,
+    damped: f32,
+}
+
+impl<const N: usize> FeedbackComb<N> {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            buffer: RingBuffer::new(),
+            delay: 1.0,
+            feedback: 0.0,
+            damping: 0.0,
+            damped: 0.0,
+        }
+    }
+
+    /// Set delay time in seconds. It can be fractional.
+    ///
+    /// It gets clamped between one sample and the size of the buffer.
+    pub fn set_delay(&mut self, time: f32) -> &mut Self {
+        self.delay = clamp_delay::<N>(time * self.sample_rate as f32);
+        self
+    }
+
+    /// Set delay to the period of the given frequency, tuning the comb so
+    /// it resonates on its harmonics.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.delay = clamp_delay::<N>(self.sample_rate as f32 / frequency);
+        self
+    }
+
+    /// Set amount of delayed signal fed back. Keep it below 1.0 for the
+    /// filter to remain stable.
+    pub fn set_feedback(&mut self, feedback: f32) -> &mut Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// Set damping between 0.0 and 1.0. It controls a one-pole low pass
+    /// filter in the feedback loop, making high frequencies decay faster.
+    pub fn set_damping(&mut self, damping: f32) -> &mut Self {
+        self.damping = damping.clamp(0.0, 1.0);
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let delayed = self.buffer.peek_interpolated(1.0 - self.delay);
+        self.damped = delayed * (1.0 - self.damping) + self.damped * self.damping;
+        self.buffer.write(value + self.damped * self.feedback);
+        delayed
+    }
+}
+
+/// Comb filter mixing the input with its delayed copy.
+///
+/// Size of the buffer `N` limits the longest delay in samples.
+pub struct FeedforwardComb<const N: usize> {
+    sample_rate: u32,
+    buffer: RingBuffer<N>,
+    delay: f32,
+    gain: f32,
+}
+
+impl<const N: usize> FeedforwardComb<N> {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            buffer: RingBuffer::new(),
+            delay: 1.0,
+            gain: 0.0,
+        }
+    }
+
+    /// Set delay time in seconds. It can be fractional.
+    ///
+    /// It gets clamped between one sample and the size of the buffer.
+    pub fn set_delay(&mut self, time: f32) -> &mut Self {
+        self.delay = clamp_delay::<N>(time * self.sample_rate as f32);
+        self
+    }
+
+    /// Set gain of the delayed copy. Negative gain cancels the frequencies
+    /// which would be boosted by a positive one.
+    pub fn set_gain(&mut self, gain: f32) -> &mut Self {
+        self.gain = gain;
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let delayed = self.buffer.peek_interpolated(1.0 - self.delay);
+        self.buffer.write(value);
+        value + delayed * self.gain
+    }
+}
+
+pub(crate) fn clamp_delay<const N: usize>(samples: f32) -> f32 {
+    samples.clamp(1.0, N as f32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+    use crate::test_utils::impulse;
+
+    const SAMPLE_RATE: u32 = 1024;
+
+    #[test]
+    fn initialize_combs() {
+        const SAMPLE_RATE: u32 = 1;
+        let _feedback = FeedbackComb::<8>::new(SAMPLE_RATE);
+        let _feedforward = FeedforwardComb::<8>::new(SAMPLE_RATE);
+    }
+
+    #[test]
+    fn feedback_comb_repeats_decaying_impulse() {
+        let mut comb = FeedbackComb::<16>::new(SAMPLE_RATE);
+        comb.set_delay(4.0 / SAMPLE_RATE as f32).set_feedback(0.5);
+
+        let mut signal = impulse();
+        comb.process(&mut signal);
+
+        assert_relative_eq!(signal[4], 1.0);
+        assert_relative_eq!(signal[8], 0.5);
+        assert_relative_eq!(signal[12], 0.25);
+        assert_relative_eq!(signal[5], 0.0);
+    }
+
+    #[test]
+    fn feedback_comb_delays_by_fraction_of_sample() {
+        let mut comb = FeedbackComb::<16>::new(SAMPLE_RATE);
+        comb.set_delay(2.5 / SAMPLE_RATE as f32);
+
+        let mut signal = impulse();
+        comb.process(&mut signal);
+
+        assert_relative_eq!(signal[2], 0.5);
+        assert_relative_eq!(signal[3], 0.5);
+    }
+
+    #[test]
+    fn feedback_comb_resonates_on_set_frequency() {
+        let mut comb = FeedbackComb::<128>::new(SAMPLE_RATE);
+        comb.set_frequency(100.0).set_feedback(0.9);
+
+        let mut signal = impulse();
+        comb.process(&mut signal);
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        assert!(analysis.magnitude(100.0) / analysis.magnitude(150.0) > 10.0);
+        assert!(analysis.magnitude(200.0) / analysis.magnitude(250.0) > 10.0);
+    }
+
+    #[test]
+    fn damping_attenuates_high_harmonics() {
+        let harmonics_ratio = |damping| {
+            let mut comb = FeedbackComb::<128>::new(SAMPLE_RATE);
+            comb.set_frequency(64.0)
+                .set_feedback(0.9)
+                .set_damping(damping);
+
+            let mut signal = impulse();
+            comb.process(&mut signal);
+
+            let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+            let low_harmonic = analysis.mean_magnitude(56.0, 72.0);
+            let high_harmonic = analysis.mean_magnitude(440.0, 456.0);
+            low_harmonic / high_harmonic
+        };
+
+        assert_relative_eq!(harmonics_ratio(0.0), 1.0, epsilon = 0.1);
+        assert!(harmonics_ratio(0.5) > 3.0);
+    }
+
+    #[test]
+    fn feedforward_comb_cancels_frequency() {
+        let mut comb = FeedforwardComb::<16>::new(SAMPLE_RATE);
+        comb.set_delay(4.0 / SAMPLE_RATE as f32).set_gain(1.0);
+
+        let mut signal = impulse();
+        comb.process(&mut signal);
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        assert!(analysis.magnitude(128.0) < 0.01);
+        assert_relative_eq!(analysis.magnitude(0.0), 4.0, epsilon = 0.01);
+    }
+
+    #[test]
+    fn clamp_delay_to_buffer_size() {
+        let mut comb = FeedbackComb::<4>::new(SAMPLE_RATE);
+        comb.set_delay(10.0);
+
+        let mut signal = impulse();
+        comb.process(&mut signal);
+
+        assert_relative_eq!(signal[4], 1.0);
+    }
+}
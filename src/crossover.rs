@@ -0,0 +1,366 @@
+//! Linkwitz-Riley crossovers splitting signal into multiple bands.
+//!
+//! Each split is done by a pair of 24 dB per octave Linkwitz-Riley low and
+//! high pass filters built from [`CascadedFilter`]. Their outputs are in
+//! phase, so the bands sum back to a signal of the original magnitude
+//! spectrum. Bands split off early pass through all-pass filters matching
+//! the phase shift of later splits, so this holds for any number of bands.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sirena::crossover::ThreeBandCrossover;
+//!
+//! let mut crossover = ThreeBandCrossover::new(48000);
+//! crossover.set_low_frequency(200.0).set_high_frequency(2000.0);
+//!
+//! let [low, mid, high] = crossover.tick(1.0);
+//! ```
+
+use core::f32::consts::FRAC_1_SQRT_2;
+
+use crate::biquad::{self, Biquad};
+use crate::cascaded_filter::{CascadedFilter, Db24, HighPass, LinkwitzRiley, LowPass};
+
+/// Splits signal into two bands.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct TwoBandCrossover {
+    splitter: Splitter<1, 0>,
+}
+
+impl TwoBandCrossover {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            splitter: Splitter::new(sample_rate),
+        }
+    }
+
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.splitter.set_frequency(0, frequency);
+        self
+    }
+
+    /// Split the signal, writing bands from the lowest to the highest.
+    ///
+    /// # Panics
+    ///
+    /// All bands must be as long as the signal.
+    pub fn process(&mut self, signal: &[f32], bands: [&mut [f32]; 2]) {
+        assert_band_lengths(signal, &bands);
+        let [low, high] = bands;
+        for (i, x) in signal.iter().enumerate() {
+            [low[i], high[i]] = self.tick(*x);
+        }
+    }
+
+    /// Split the sample, returning bands from the lowest to the highest.
+    pub fn tick(&mut self, value: f32) -> [f32; 2] {
+        let mut bands = [0.0; 2];
+        self.splitter.tick(value, &mut bands);
+        bands
+    }
+}
+
+/// Splits signal into low, mid and high band.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct ThreeBandCrossover {
+    splitter: Splitter<2, 1>,
+}
+
+impl ThreeBandCrossover {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            splitter: Splitter::new(sample_rate),
+        }
+    }
+
+    /// Set frequency between low and mid band.
+    pub fn set_low_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.splitter.set_frequency(0, frequency);
+        self
+    }
+
+    /// Set frequency between mid and high band.
+    pub fn set_high_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.splitter.set_frequency(1, frequency);
+        self
+    }
+
+    /// Split the signal, writing bands from the lowest to the highest.
+    ///
+    /// # Panics
+    ///
+    /// All bands must be as long as the signal.
+    pub fn process(&mut self, signal: &[f32], bands: [&mut [f32]; 3]) {
+        assert_band_lengths(signal, &bands);
+        let [low, mid, high] = bands;
+        for (i, x) in signal.iter().enumerate() {
+            [low[i], mid[i], high[i]] = self.tick(*x);
+        }
+    }
+
+    /// Split the sample, returning bands from the lowest to the highest.
+    pub fn tick(&mut self, value: f32) -> [f32; 3] {
+        let mut bands = [0.0; 3];
+        self.splitter.tick(value, &mut bands);
+        bands
+    }
+}
+
+/// Splits signal into low, low mid, high mid and high band.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct FourBandCrossover {
+    splitter: Splitter<3, 3>,
+}
+
+impl FourBandCrossover {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            splitter: Splitter::new(sample_rate),
+        }
+    }
+
+    /// Set frequency between low and low mid band.
+    pub fn set_low_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.splitter.set_frequency(0, frequency);
+        self
+    }
+
+    /// Set frequency between low mid and high mid band.
+    pub fn set_mid_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.splitter.set_frequency(1, frequency);
+        self
+    }
+
+    /// Set frequency between high mid and high band.
+    pub fn set_high_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.splitter.set_frequency(2, frequency);
+        self
+    }
+
+    /// Split the signal, writing bands from the lowest to the highest.
+    ///
+    /// # Panics
+    ///
+    /// All bands must be as long as the signal.
+    pub fn process(&mut self, signal: &[f32], bands: [&mut [f32]; 4]) {
+        assert_band_lengths(signal, &bands);
+        let [low, low_mid, high_mid, high] = bands;
+        for (i, x) in signal.iter().enumerate() {
+            [low[i], low_mid[i], high_mid[i], high[i]] = self.tick(*x);
+        }
+    }
+
+    /// Split the sample, returning bands from the lowest to the highest.
+    pub fn tick(&mut self, value: f32) -> [f32; 4] {
+        let mut bands = [0.0; 4];
+        self.splitter.tick(value, &mut bands);
+        bands
+    }
+}
+
+fn assert_band_lengths(signal: &[f32], bands: &[&mut [f32]]) {
+    assert!(
+        bands.iter().all(|band| band.len() == signal.len()),
+        "All bands must be as long as the signal"
+    );
+}
+
+// Splits the signal repeatedly, each time taking away the lowest band. All
+// bands but the last one are then compensated by all-pass filters matching
+// phase shift of splits they did not go through.
+//
+// Split `j` needs to be compensated on all `j` bands below it, so there are
+// `SPLITS * (SPLITS - 1) / 2` all-pass filters in total, passed in as
+// `COMPENSATIONS`. They are stored grouped by the split, starting with the
+// lowest band.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+struct Splitter<const SPLITS: usize, const COMPENSATIONS: usize> {
+    splits: [Split; SPLITS],
+    compensation: [Biquad; COMPENSATIONS],
+}
+
+impl<const SPLITS: usize, const COMPENSATIONS: usize> Splitter<SPLITS, COMPENSATIONS> {
+    fn new(sample_rate: u32) -> Self {
+        assert_eq!(COMPENSATIONS, SPLITS * (SPLITS - 1) / 2);
+        Self {
+            splits: core::array::from_fn(|_| Split::new(sample_rate)),
+            compensation: core::array::from_fn(|_| {
+                let mut all_pass = Biquad::new(sample_rate);
+                all_pass
+                    .set_bandform(biquad::AllPass)
+                    .set_q_factor(FRAC_1_SQRT_2);
+                all_pass
+            }),
+        }
+    }
+
+    fn set_frequency(&mut self, split: usize, frequency: f32) {
+        self.splits[split].set_frequency(frequency);
+        let offset = compensation_offset(split);
+        for all_pass in self.compensation[offset..offset + split].iter_mut() {
+            all_pass.set_frequency(frequency);
+        }
+    }
+
+    fn tick(&mut self, value: f32, bands: &mut [f32]) {
+        let mut rest = value;
+        for (band, split) in bands.iter_mut().zip(self.splits.iter_mut()) {
+            let (low, high) = split.tick(rest);
+            *band = low;
+            rest = high;
+        }
+        bands[SPLITS] = rest;
+
+        for (i, band) in bands.iter_mut().enumerate() {
+            for split in i + 1..SPLITS {
+                *band = self.compensation[compensation_offset(split) + i].tick(*band);
+            }
+        }
+    }
+}
+
+fn compensation_offset(split: usize) -> usize {
+    split * split.saturating_sub(1) / 2
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+struct Split {
+    low_pass: CascadedFilter,
+    high_pass: CascadedFilter,
+}
+
+impl Split {
+    fn new(sample_rate: u32) -> Self {
+        let mut low_pass = CascadedFilter::new(sample_rate);
+        low_pass
+            .set_bandform(LowPass)
+            .set_slope(Db24)
+            .set_alignment(LinkwitzRiley);
+        let mut high_pass = CascadedFilter::new(sample_rate);
+        high_pass
+            .set_bandform(HighPass)
+            .set_slope(Db24)
+            .set_alignment(LinkwitzRiley);
+        Self {
+            low_pass,
+            high_pass,
+        }
+    }
+
+    fn set_frequency(&mut self, frequency: f32) {
+        self.low_pass.set_frequency(frequency);
+        self.high_pass.set_frequency(frequency);
+    }
+
+    fn tick(&mut self, value: f32) -> (f32, f32) {
+        (self.low_pass.tick(value), self.high_pass.tick(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+    use crate::test_utils::impulse;
+
+    const SAMPLE_RATE: u32 = 1024;
+    const FREQUENCIES: [f32; 7] = [5.0, 20.0, 50.0, 100.0, 200.0, 300.0, 500.0];
+
+    fn assert_flat(signal: &[f32; 1024]) {
+        let analysis = SpectralAnalysis::analyze(signal, SAMPLE_RATE);
+        for frequency in FREQUENCIES {
+            assert_relative_eq!(analysis.magnitude(frequency), 1.0, epsilon = 0.02);
+        }
+    }
+
+    #[test]
+    fn initialize_crossovers() {
+        const SAMPLE_RATE: u32 = 1;
+        let _two = TwoBandCrossover::new(SAMPLE_RATE);
+        let _three = ThreeBandCrossover::new(SAMPLE_RATE);
+        let _four = FourBandCrossover::new(SAMPLE_RATE);
+    }
+
+    #[test]
+    fn two_bands_sum_flat() {
+        let mut crossover = TwoBandCrossover::new(SAMPLE_RATE);
+        crossover.set_frequency(100.0);
+
+        let mut signal = impulse();
+        for x in signal.iter_mut() {
+            *x = crossover.tick(*x).iter().sum();
+        }
+
+        assert_flat(&signal);
+    }
+
+    #[test]
+    fn three_bands_sum_flat() {
+        let mut crossover = ThreeBandCrossover::new(SAMPLE_RATE);
+        crossover.set_low_frequency(50.0).set_high_frequency(200.0);
+
+        let mut signal = impulse();
+        for x in signal.iter_mut() {
+            *x = crossover.tick(*x).iter().sum();
+        }
+
+        assert_flat(&signal);
+    }
+
+    #[test]
+    fn four_bands_sum_flat() {
+        let mut crossover = FourBandCrossover::new(SAMPLE_RATE);
+        crossover
+            .set_low_frequency(30.0)
+            .set_mid_frequency(100.0)
+            .set_high_frequency(300.0);
+
+        let mut signal = impulse();
+        for x in signal.iter_mut() {
+            *x = crossover.tick(*x).iter().sum();
+        }
+
+        assert_flat(&signal);
+    }
+
+    #[test]
+    fn split_signal_into_bands() {
+        let mut crossover = ThreeBandCrossover::new(SAMPLE_RATE);
+        crossover.set_low_frequency(50.0).set_high_frequency(200.0);
+
+        let signal = impulse();
+        let mut low = [0.0; 1024];
+        let mut mid = [0.0; 1024];
+        let mut high = [0.0; 1024];
+        crossover.process(&signal, [&mut low, &mut mid, &mut high]);
+
+        let low = SpectralAnalysis::analyze(&low, SAMPLE_RATE);
+        let mid = SpectralAnalysis::analyze(&mid, SAMPLE_RATE);
+        let high = SpectralAnalysis::analyze(&high, SAMPLE_RATE);
+
+        assert_relative_eq!(low.magnitude(10.0), 1.0, epsilon = 0.05);
+        assert!(low.magnitude(200.0) < 0.01);
+        assert_relative_eq!(mid.magnitude(100.0), 1.0, epsilon = 0.2);
+        assert!(mid.magnitude(10.0) < 0.01);
+        assert!(mid.magnitude(500.0) < 0.01);
+        assert_relative_eq!(high.magnitude(500.0), 1.0, epsilon = 0.05);
+        assert!(high.magnitude(50.0) < 0.01);
+    }
+
+    #[test]
+    #[should_panic]
+    fn panic_when_band_is_shorter_than_signal() {
+        let mut crossover = TwoBandCrossover::new(SAMPLE_RATE);
+
+        let signal = [0.0; 8];
+        let mut low = [0.0; 8];
+        let mut high = [0.0; 4];
+        crossover.process(&signal, [&mut low, &mut high]);
+    }
+}
@@ -0,0 +1,413 @@
+//! Delay effect with modulated delay time and feedback.
+//!
+//! The delay writes into a ring buffer given on initialization. For short
+//! delays it can be a [`RingBuffer`](crate::ring_buffer::RingBuffer), longer
+//! delays can use a [`SliceRingBuffer`](crate::ring_buffer::SliceRingBuffer)
+//! over a slice allocated by
+//! [`MemoryManager`](crate::memory_manager::MemoryManager), e.g. in an
+//! external RAM.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sirena::delay::Delay;
+//! use sirena::one_pole::LowPass;
+//! use sirena::ring_buffer::RingBuffer;
+//!
+//! let mut low_pass = LowPass::new(48000);
+//! low_pass.set_frequency(3000.0);
+//!
+//! let mut delay = Delay::new(48000, RingBuffer::<4800>::new())
+//!     .with_filter(move |x| low_pass.tick(x));
+//! delay.set_time(0.08).set_feedback(0.6).set_mix(0.5);
+//!
+//! let mut buffer = [0.0; 32];
+//! delay.process(&mut buffer);
+//! ```
+
+use crate::ring_buffer::{Buffer, Interpolator, Linear};
+use crate::signal::Signal;
+use crate::smoothing::OnePole;
+
+/// Yields delayed signal mixed with the input.
+///
+/// Buffer `B` holds the delayed signal, its capacity limits the longest
+/// delay in samples. Filter `F` is applied on the signal fed back to the buffer.
+/// Interpolator `I` reads the buffer between samples.
+pub struct Delay<B, F = fn(f32) -> f32, I = Linear> {
+    sample_rate: u32,
+    buffer: B,
+    time: OnePole,
+    feedback: f32,
+    filter: F,
+    interpolator: I,
+    mix: f32,
+    freeze: bool,
+}
+
+impl<B> Delay<B>
+where
+    B: Buffer,
+{
+    /// Initialize the delay with the shortest possible time, no feedback and
+    /// completely wet output.
+    ///
+    /// # Panics
+    ///
+    /// The buffer must not be empty.
+    pub fn new(sample_rate: u32, buffer: B) -> Self {
+        assert!(buffer.capacity() > 0);
+        let mut time = OnePole::new(sample_rate);
+        time.reset(1.0);
+        Self {
+            sample_rate,
+            buffer,
+            time,
+            feedback: 0.0,
+            filter: core::convert::identity,
+            interpolator: Linear,
+            mix: 1.0,
+            freeze: false,
+        }
+    }
+}
+
+impl<B, F, I> Delay<B, F, I>
+where
+    B: Buffer,
+    F: FnMut(f32) -> f32,
+    I: Interpolator,
+{
+    /// Insert a filter into the feedback loop, e.g. a low pass making
+    /// repeats darker.
+    pub fn with_filter<G>(self, filter: G) -> Delay<B, G, I>
+    where
+        G: FnMut(f32) -> f32,
+    {
+        Delay {
+            sample_rate: self.sample_rate,
+            buffer: self.buffer,
+            time: self.time,
+            feedback: self.feedback,
+            filter,
+            interpolator: self.interpolator,
+            mix: self.mix,
+            freeze: self.freeze,
+        }
+    }
+
+    /// Replace the default linear interpolation used on fractional delay
+    /// times, e.g. by [`AllPass`](crate::ring_buffer::AllPass) keeping
+    /// brightness of the repeats.
+    ///
+    /// Delay time gets clamped to the interpolator's `MIN_DELAY`, two
+    /// samples for `Hermite`, `Lagrange` and `AllPass`.
+    pub fn with_interpolator<J>(self, interpolator: J) -> Delay<B, F, J>
+    where
+        J: Interpolator,
+    {
+        let mut time = self.time;
+        if time.value() < J::MIN_DELAY {
+            time.reset(J::MIN_DELAY);
+        }
+        Delay {
+            sample_rate: self.sample_rate,
+            buffer: self.buffer,
+            time,
+            feedback: self.feedback,
+            filter: self.filter,
+            interpolator,
+            mix: self.mix,
+            freeze: self.freeze,
+        }
+    }
+
+    /// Set delay time in seconds. It can be fractional.
+    ///
+    /// It gets clamped between the `MIN_DELAY` of the interpolator, one
+    /// sample by default, and the capacity of the buffer minus one sample.
+    pub fn set_time(&mut self, time: f32) -> &mut Self {
+        let samples = self.samples(time);
+        self.time.set_target(samples);
+        self
+    }
+
+    /// Set time constant in seconds of smoothing applied on changes of the
+    /// delay time. Slow smoothing results in tape-like pitch bends.
+    pub fn set_smoothing(&mut self, time: f32) -> &mut Self {
+        self.time.set_time(time);
+        self
+    }
+
+    /// Set amount of delayed signal fed back. Keep it below 1.0 for the
+    /// repeats to fade out.
+    pub fn set_feedback(&mut self, feedback: f32) -> &mut Self {
+        self.feedback = feedback;
+        self
+    }
+
+    /// Set ratio between the dry input (0.0) and the delayed signal (1.0).
+    pub fn set_mix(&mut self, mix: f32) -> &mut Self {
+        self.mix = mix.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Stop writing the input to the buffer and keep repeating its current
+    /// content.
+    pub fn set_freeze(&mut self, freeze: bool) -> &mut Self {
+        self.freeze = freeze;
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    /// Process the given signal, with the delay time in seconds taken from
+    /// given signal. Smoothing applies on the modulated time.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::delay::Delay;
+    /// use sirena::ring_buffer::RingBuffer;
+    /// use sirena::signal::{self, SignalOffsetAmp, SignalScaleAmp};
+    ///
+    /// let mut delay = Delay::new(48000, RingBuffer::<4800>::new());
+    ///
+    /// let time = signal::sine(48000.0, 0.5).scale_amp(0.002).offset_amp(0.02);
+    ///
+    /// let mut buffer = [0.0; 32];
+    /// delay.process_modulated(&mut buffer, time);
+    /// ```
+    pub fn process_modulated<T>(&mut self, signal: &mut [f32], mut time: T)
+    where
+        T: Signal,
+    {
+        for x in signal.iter_mut() {
+            *x = self.tick_modulated(*x, time.next());
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let time = self.time.tick();
+        let delayed = self.buffer.peek_with(1.0 - time, &mut self.interpolator);
+
+        let input = if self.freeze {
+            delayed
+        } else {
+            value + (self.filter)(delayed) * self.feedback
+        };
+        self.buffer.write(input);
+
+        value * (1.0 - self.mix) + delayed * self.mix
+    }
+
+    /// Delay a single sample using delay time in seconds given for this
+    /// tick.
+    pub fn tick_modulated(&mut self, value: f32, time: f32) -> f32 {
+        let samples = self.samples(time);
+        self.time.set_target(samples);
+        self.tick(value)
+    }
+
+    fn samples(&self, time: f32) -> f32 {
+        let max = f32::max(self.buffer.capacity() as f32 - 1.0, I::MIN_DELAY);
+        (time * self.sample_rate as f32).clamp(I::MIN_DELAY, max)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_manager::MemoryManager;
+    use crate::ring_buffer::{Hermite, RingBuffer, SliceRingBuffer};
+    use crate::signal;
+    use crate::test_utils::impulse;
+    use core::mem::MaybeUninit;
+
+    const SAMPLE_RATE: u32 = 1000;
+
+    #[test]
+    fn initialize_delay() {
+        const SAMPLE_RATE: u32 = 1;
+        let _delay = Delay::new(SAMPLE_RATE, RingBuffer::<2>::new());
+    }
+
+    #[test]
+    #[should_panic]
+    fn panic_on_empty_buffer() {
+        let _delay = Delay::new(SAMPLE_RATE, RingBuffer::<0>::new());
+    }
+
+    #[test]
+    fn delay_impulse_by_set_time() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new());
+        delay.set_time(0.005);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[5], 1.0);
+        assert_relative_eq!(signal.iter().sum::<f32>(), 1.0);
+    }
+
+    #[test]
+    fn delay_by_fraction_of_sample() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new());
+        delay.set_time(0.0025);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[2], 0.5);
+        assert_relative_eq!(signal[3], 0.5);
+    }
+
+    #[test]
+    fn read_fraction_of_sample_with_given_interpolator() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new()).with_interpolator(Hermite);
+        delay.set_time(0.0035);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[2], -0.0625);
+        assert_relative_eq!(signal[3], 0.5625);
+        assert_relative_eq!(signal[4], 0.5625);
+        assert_relative_eq!(signal[5], -0.0625);
+    }
+
+    #[test]
+    fn clamp_time_to_minimal_delay_of_interpolator() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new()).with_interpolator(Hermite);
+        delay.set_time(0.0015);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[1], 0.0);
+        assert_relative_eq!(signal[2], 1.0);
+        assert_relative_eq!(signal[3], 0.0);
+    }
+
+    #[test]
+    fn repeat_impulse_with_feedback() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new());
+        delay.set_time(0.01).set_feedback(0.5);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[10], 1.0);
+        assert_relative_eq!(signal[20], 0.5);
+        assert_relative_eq!(signal[30], 0.25);
+    }
+
+    #[test]
+    fn apply_filter_in_feedback_loop() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new()).with_filter(|x| -x);
+        delay.set_time(0.01).set_feedback(0.5);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[10], 1.0);
+        assert_relative_eq!(signal[20], -0.5);
+        assert_relative_eq!(signal[30], 0.25);
+    }
+
+    #[test]
+    fn mix_dry_and_wet_signal() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new());
+        delay.set_time(0.01).set_mix(0.25);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[0], 0.75);
+        assert_relative_eq!(signal[10], 0.25);
+    }
+
+    #[test]
+    fn freeze_keeps_repeating_buffer() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new());
+        delay.set_time(0.01);
+
+        let mut signal = impulse();
+        delay.process(&mut signal[..10]);
+        delay.set_freeze(true);
+        signal[15] = 1.0;
+        delay.process(&mut signal[10..]);
+
+        assert_relative_eq!(signal[10], 1.0);
+        assert_relative_eq!(signal[20], 1.0);
+        assert_relative_eq!(signal[60], 1.0);
+        assert_relative_eq!(signal[25], 0.0);
+    }
+
+    #[test]
+    fn clamp_time_to_buffer_length() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<8>::new());
+        delay.set_time(1.0);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[7], 1.0);
+    }
+
+    #[test]
+    fn smoothen_changes_of_time() {
+        let echo = |smoothing: f32, time: f32| {
+            let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<64>::new());
+            delay.set_smoothing(smoothing).set_time(time);
+
+            let mut signal = [0.0; 64];
+            signal[0] = 1.0;
+            delay.process(&mut signal);
+
+            signal.iter().position(|x| *x > 0.1).unwrap()
+        };
+
+        let initial = echo(0.0, 0.001);
+        let target = echo(0.0, 0.03);
+        let smoothed = echo(0.01, 0.03);
+        assert_eq!(initial, 1);
+        assert_eq!(target, 30);
+        assert!(smoothed > initial && smoothed < target);
+    }
+
+    #[test]
+    fn modulate_time_with_signal() {
+        let mut delay = Delay::new(SAMPLE_RATE, RingBuffer::<16>::new());
+
+        let mut signal = impulse();
+        delay.process_modulated(&mut signal, signal::constant(0.005));
+
+        assert_relative_eq!(signal[5], 1.0);
+    }
+
+    #[test]
+    fn write_into_allocated_memory() {
+        static mut MEMORY: [MaybeUninit<u32>; 32] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut memory_manager = MemoryManager::from(unsafe { &mut MEMORY[..] });
+
+        let buffer = SliceRingBuffer::from(memory_manager.allocate(32).unwrap());
+        let mut delay = Delay::new(SAMPLE_RATE, buffer);
+        delay.set_time(0.02);
+
+        let mut signal = impulse();
+        delay.process(&mut signal);
+
+        assert_relative_eq!(signal[20], 1.0);
+    }
+}
@@ -0,0 +1,596 @@
+//! Envelope generator shaping amplitude of a voice over time.
+//!
+//! The envelope supports AR, ADSR and AHDSR modes with linear or exponential
+//! segments. It is driven either by calling `gate_on` and `gate_off`, or by a
+//! gate signal passed to `gated`. Being a `Signal` itself, it can be applied
+//! on an oscillator using [`mul_amp`](crate::signal::SignalMulAmp).
+//!
+//! Linear attack, decay and release times are measured for a transition
+//! across the full scale between `0.0` and `1.0`. Shorter transitions, e.g. a
+//! decay to a high sustain level, take proportionally less time. Exponential
+//! decay and release reach their target exactly in the set time, wherever
+//! they start from.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sirena::envelope::{Adsr, Envelope};
+//! use sirena::signal::{self, Signal, SignalMulAmp};
+//!
+//! let mut envelope = Envelope::new(48000);
+//! envelope
+//!     .set_mode(Adsr)
+//!     .set_attack(0.01)
+//!     .set_decay(0.1)
+//!     .set_sustain(0.5)
+//!     .set_release(0.3);
+//! envelope.gate_on();
+//!
+//! let mut voice = signal::sine(48000.0, 440.0).mul_amp(&mut envelope);
+//! voice.next();
+//! ```
+
+use crate::signal::Signal;
+
+/// Overshoot of exponential attack target, defining its curvature.
+const ATTACK_RATIO: f32 = 0.3;
+
+/// Undershoot of exponential decay and release targets, defining their
+/// curvature.
+const DECAY_RATIO: f32 = 0.0001;
+
+/// Gate signal above this value is considered open.
+pub const GATE_THRESHOLD: f32 = 0.5;
+
+/// Yields envelope shaping a voice.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct Envelope {
+    sample_rate: u32,
+    mode: Mode,
+    curve: Curve,
+    legato: bool,
+    attack_time: f32,
+    hold_time: f32,
+    decay_time: f32,
+    sustain: f32,
+    release_time: f32,
+    attack: Segment,
+    decay: Segment,
+    release: Segment,
+    hold_samples: u32,
+    hold_counter: u32,
+    stage: Stage,
+    level: f32,
+}
+
+impl Envelope {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut envelope = Self {
+            sample_rate,
+            mode: Adsr,
+            curve: Linear,
+            legato: false,
+            attack_time: 0.0,
+            hold_time: 0.0,
+            decay_time: 0.0,
+            sustain: 1.0,
+            release_time: 0.0,
+            attack: Segment::default(),
+            decay: Segment::default(),
+            release: Segment::default(),
+            hold_samples: 0,
+            hold_counter: 0,
+            stage: Stage::Idle,
+            level: 0.0,
+        };
+        envelope.update_segments();
+        envelope
+    }
+
+    pub fn set_mode(&mut self, mode: Mode) -> &mut Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn set_curve(&mut self, curve: Curve) -> &mut Self {
+        self.curve = curve;
+        self.update_segments();
+        self
+    }
+
+    /// With legato enabled, opening the gate while the envelope is still
+    /// open does not retrigger it.
+    pub fn set_legato(&mut self, legato: bool) -> &mut Self {
+        self.legato = legato;
+        self
+    }
+
+    /// Set attack time in seconds.
+    pub fn set_attack(&mut self, attack: f32) -> &mut Self {
+        self.attack_time = attack;
+        self.update_segments();
+        self
+    }
+
+    /// Set hold time in seconds, used only in AHDSR mode.
+    pub fn set_hold(&mut self, hold: f32) -> &mut Self {
+        self.hold_time = hold;
+        self.update_segments();
+        self
+    }
+
+    /// Set decay time in seconds.
+    pub fn set_decay(&mut self, decay: f32) -> &mut Self {
+        self.decay_time = decay;
+        self.update_segments();
+        self
+    }
+
+    /// Set sustain level between `0.0` and `1.0`.
+    pub fn set_sustain(&mut self, sustain: f32) -> &mut Self {
+        self.sustain = sustain.clamp(0.0, 1.0);
+        self.update_segments();
+        self
+    }
+
+    /// Set release time in seconds.
+    pub fn set_release(&mut self, release: f32) -> &mut Self {
+        self.release_time = release;
+        self.update_segments();
+        self
+    }
+
+    /// Open the gate, starting the attack from the current level.
+    pub fn gate_on(&mut self) {
+        let open = !matches!(self.stage, Stage::Idle | Stage::Release);
+        if self.legato && open {
+            return;
+        }
+        self.stage = Stage::Attack;
+    }
+
+    /// Close the gate, starting the release.
+    ///
+    /// In AR mode the release starts right after the attack regardless of the
+    /// gate.
+    pub fn gate_off(&mut self) {
+        if self.stage != Stage::Idle {
+            self.start_release();
+        }
+    }
+
+    /// Whether the envelope finished its release and stays silent.
+    pub fn is_idle(&self) -> bool {
+        self.stage == Stage::Idle
+    }
+
+    /// Drive the envelope by the given gate signal.
+    ///
+    /// The gate opens when the signal rises above [`GATE_THRESHOLD`] and
+    /// closes when it falls back below it.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::envelope::Envelope;
+    /// use sirena::signal::{self, Signal};
+    ///
+    /// let gate = signal::from_iter([1.0, 1.0, 0.0]);
+    /// let mut envelope = Envelope::new(4);
+    /// envelope.set_attack(0.5).set_release(0.5);
+    ///
+    /// let mut envelope = envelope.gated(gate);
+    /// assert_eq!(envelope.next(), 0.5);
+    /// assert_eq!(envelope.next(), 1.0);
+    /// assert_eq!(envelope.next(), 0.5);
+    /// assert_eq!(envelope.next(), 0.0);
+    /// ```
+    pub fn gated<G>(self, gate: G) -> Gated<G>
+    where
+        G: Signal,
+    {
+        Gated {
+            envelope: self,
+            gate,
+            open: false,
+        }
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        match self.stage {
+            Stage::Idle => (),
+            Stage::Attack => {
+                self.level = self.attack.apply(self.level);
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.hold_counter = 0;
+                    match self.mode {
+                        Ar => self.start_release(),
+                        Ahdsr if self.hold_samples > 0 => self.stage = Stage::Hold,
+                        Adsr | Ahdsr => self.stage = Stage::Decay,
+                    }
+                }
+            }
+            Stage::Hold => {
+                self.hold_counter += 1;
+                if self.hold_counter >= self.hold_samples {
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                self.level = self.decay.apply(self.level);
+                if self.level <= self.sustain {
+                    self.level = self.sustain;
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Sustain => self.level = self.sustain,
+            Stage::Release => {
+                self.level = self.release.apply(self.level);
+                if self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = Stage::Idle;
+                }
+            }
+        }
+
+        self.level
+    }
+
+    fn start_release(&mut self) {
+        // Exponential release lands on zero in the set time only when its
+        // curve is calculated from the level it starts on.
+        if self.curve == Exponential {
+            let release_samples = self.release_time * self.sample_rate as f32;
+            self.release =
+                Segment::exponential(release_samples, self.level, -DECAY_RATIO, DECAY_RATIO);
+        }
+        self.stage = Stage::Release;
+    }
+
+    fn update_segments(&mut self) {
+        let sample_rate = self.sample_rate as f32;
+        let attack_samples = self.attack_time * sample_rate;
+        let decay_samples = self.decay_time * sample_rate;
+        let release_samples = self.release_time * sample_rate;
+
+        match self.curve {
+            Linear => {
+                self.attack = Segment::linear(attack_samples, 1.0);
+                self.decay = Segment::linear(decay_samples, -1.0);
+                self.release = Segment::linear(release_samples, -1.0);
+            }
+            Exponential => {
+                self.attack =
+                    Segment::exponential(attack_samples, 1.0, 1.0 + ATTACK_RATIO, ATTACK_RATIO);
+                self.decay = Segment::exponential(
+                    decay_samples,
+                    1.0 - self.sustain,
+                    self.sustain - DECAY_RATIO,
+                    DECAY_RATIO,
+                );
+                self.release =
+                    Segment::exponential(release_samples, 1.0, -DECAY_RATIO, DECAY_RATIO);
+            }
+        }
+
+        self.hold_samples = (self.hold_time * sample_rate) as u32;
+    }
+}
+
+impl Signal for Envelope {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.tick()
+    }
+}
+
+/// Envelope driven by a gate signal.
+pub struct Gated<G>
+where
+    G: Signal,
+{
+    envelope: Envelope,
+    gate: G,
+    open: bool,
+}
+
+impl<G> Gated<G>
+where
+    G: Signal,
+{
+    /// Access the inner envelope to change its parameters.
+    pub fn envelope_mut(&mut self) -> &mut Envelope {
+        &mut self.envelope
+    }
+}
+
+impl<G> Signal for Gated<G>
+where
+    G: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let open = self.gate.next() > GATE_THRESHOLD;
+        if open && !self.open {
+            self.envelope.gate_on();
+        } else if !open && self.open {
+            self.envelope.gate_off();
+        }
+        self.open = open;
+        self.envelope.tick()
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.gate.is_exhausted()
+    }
+}
+
+/// Stages of the envelope the generator goes through.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Attack followed immediately by release.
+    Ar,
+    /// Attack, decay, sustain while the gate is open and release.
+    Adsr,
+    /// Like ADSR, but holding on the peak for a set time before decay.
+    Ahdsr,
+}
+
+pub use Mode::*;
+
+/// Shape of envelope segments.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Curve {
+    Linear,
+    /// Attack slowing down towards its peak, decay and release slowing down
+    /// towards their targets, resembling analog envelopes. Decay and release
+    /// reach their target in the set time.
+    Exponential,
+}
+
+pub use Curve::*;
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    Idle,
+    Attack,
+    Hold,
+    Decay,
+    Sustain,
+    Release,
+}
+
+// Transition of the level calculated as `base + level * coefficient`. This
+// covers both linear segments with coefficient of 1.0 and exponential
+// segments approaching their target.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Default, Clone, Copy)]
+struct Segment {
+    base: f32,
+    coefficient: f32,
+}
+
+impl Segment {
+    fn linear(samples: f32, direction: f32) -> Self {
+        Self {
+            base: direction / f32::max(samples, 1.0),
+            coefficient: 1.0,
+        }
+    }
+
+    // Approach the target, overshooting it by the given ratio, so the
+    // segment ends in finite time. The curve is set to cross the `span`
+    // between its start and end in the given number of samples.
+    fn exponential(samples: f32, span: f32, target: f32, ratio: f32) -> Self {
+        let coefficient = if samples > 0.0 {
+            libm::expf(-libm::logf((span + ratio) / ratio) / samples)
+        } else {
+            0.0
+        };
+        Self {
+            base: target * (1.0 - coefficient),
+            coefficient,
+        }
+    }
+
+    #[inline]
+    fn apply(&self, level: f32) -> f32 {
+        self.base + level * self.coefficient
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 1000;
+
+    fn run(envelope: &mut Envelope, samples: usize) -> f32 {
+        (0..samples).fold(0.0, |_, _| envelope.tick())
+    }
+
+    #[test]
+    fn stay_silent_before_gate() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        assert_relative_eq!(run(&mut envelope, 10), 0.0);
+        assert!(envelope.is_idle());
+    }
+
+    #[test]
+    fn go_through_adsr_stages() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope
+            .set_attack(0.1)
+            .set_decay(0.1)
+            .set_sustain(0.5)
+            .set_release(0.1);
+
+        envelope.gate_on();
+        assert_relative_eq!(run(&mut envelope, 50), 0.5, epsilon = 0.01);
+        assert_relative_eq!(run(&mut envelope, 50), 1.0, epsilon = 0.01);
+        assert_relative_eq!(run(&mut envelope, 25), 0.75, epsilon = 0.02);
+        assert_relative_eq!(run(&mut envelope, 100), 0.5);
+
+        envelope.gate_off();
+        assert_relative_eq!(run(&mut envelope, 25), 0.25, epsilon = 0.01);
+        assert_relative_eq!(run(&mut envelope, 30), 0.0);
+        assert!(envelope.is_idle());
+    }
+
+    #[test]
+    fn release_right_after_attack_in_ar_mode() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope.set_mode(Ar).set_attack(0.1).set_release(0.1);
+
+        envelope.gate_on();
+        assert_relative_eq!(run(&mut envelope, 100), 1.0, epsilon = 0.01);
+        assert_relative_eq!(run(&mut envelope, 50), 0.5, epsilon = 0.02);
+        assert_relative_eq!(run(&mut envelope, 60), 0.0);
+    }
+
+    #[test]
+    fn hold_peak_in_ahdsr_mode() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope
+            .set_mode(Ahdsr)
+            .set_attack(0.1)
+            .set_hold(0.1)
+            .set_decay(0.1)
+            .set_sustain(0.0);
+
+        envelope.gate_on();
+        assert_relative_eq!(run(&mut envelope, 190), 1.0, epsilon = 0.01);
+        assert_relative_eq!(run(&mut envelope, 60), 0.5, epsilon = 0.02);
+    }
+
+    #[test]
+    fn skip_hold_of_zero_length() {
+        let mut ahdsr = Envelope::new(SAMPLE_RATE);
+        ahdsr
+            .set_mode(Ahdsr)
+            .set_attack(0.01)
+            .set_hold(0.0)
+            .set_decay(0.01)
+            .set_sustain(0.0);
+        let mut adsr = Envelope::new(SAMPLE_RATE);
+        adsr.set_attack(0.01).set_decay(0.01).set_sustain(0.0);
+
+        ahdsr.gate_on();
+        adsr.gate_on();
+        for _ in 0..30 {
+            assert_relative_eq!(ahdsr.tick(), adsr.tick());
+        }
+    }
+
+    #[test]
+    fn reach_sustain_in_exponential_decay_time() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope
+            .set_curve(Exponential)
+            .set_decay(0.1)
+            .set_sustain(0.5);
+
+        envelope.gate_on();
+        envelope.tick();
+        let samples = (0..200).position(|_| envelope.tick() <= 0.5).unwrap();
+        assert!((98..=101).contains(&samples));
+    }
+
+    #[test]
+    fn release_from_sustain_in_exponential_release_time() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope
+            .set_curve(Exponential)
+            .set_sustain(0.5)
+            .set_release(0.1);
+
+        envelope.gate_on();
+        run(&mut envelope, 10);
+        envelope.gate_off();
+        let samples = (0..200).position(|_| envelope.tick() <= 0.0).unwrap();
+        assert!((98..=101).contains(&samples));
+    }
+
+    #[test]
+    fn bend_exponential_attack_above_linear() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope.set_curve(Exponential).set_attack(0.1);
+
+        envelope.gate_on();
+        assert!(run(&mut envelope, 50) > 0.6);
+        assert_relative_eq!(run(&mut envelope, 50), 1.0, epsilon = 0.02);
+    }
+
+    #[test]
+    fn bend_exponential_release_below_linear() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope.set_curve(Exponential).set_release(0.1);
+
+        envelope.gate_on();
+        run(&mut envelope, 10);
+        envelope.gate_off();
+        assert!(run(&mut envelope, 50) < 0.1);
+        assert_relative_eq!(run(&mut envelope, 60), 0.0);
+    }
+
+    #[test]
+    fn retrigger_from_current_level() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope.set_attack(0.1).set_release(0.1);
+
+        envelope.gate_on();
+        run(&mut envelope, 100);
+        envelope.gate_off();
+        let released = run(&mut envelope, 50);
+
+        envelope.gate_on();
+        assert!(envelope.tick() > released);
+    }
+
+    #[test]
+    fn restart_attack_when_retriggering_without_legato() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope.set_attack(0.1).set_decay(0.1).set_sustain(0.5);
+
+        envelope.gate_on();
+        run(&mut envelope, 300);
+        envelope.gate_on();
+        assert!(run(&mut envelope, 10) > 0.5);
+    }
+
+    #[test]
+    fn ignore_retrigger_with_legato() {
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope
+            .set_legato(true)
+            .set_attack(0.1)
+            .set_decay(0.1)
+            .set_sustain(0.5);
+
+        envelope.gate_on();
+        run(&mut envelope, 300);
+        envelope.gate_on();
+        assert_relative_eq!(run(&mut envelope, 10), 0.5);
+    }
+
+    #[test]
+    fn follow_gate_signal() {
+        use crate::signal::from_iter;
+
+        let gate = from_iter([1.0; 20].into_iter().chain([0.0; 20]));
+        let mut envelope = Envelope::new(SAMPLE_RATE);
+        envelope.set_attack(0.01).set_release(0.01);
+        let mut envelope = envelope.gated(gate);
+
+        let open = (0..20).fold(0.0, |_, _| envelope.next());
+        assert_relative_eq!(open, 1.0);
+        let closed = (0..20).fold(0.0, |_, _| envelope.next());
+        assert_relative_eq!(closed, 0.0);
+    }
+}
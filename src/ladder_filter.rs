@@ -0,0 +1,194 @@
+//! Moog-style transistor ladder filter.
+//!
+//! Four one-pole low pass stages are chained and their output is fed back
+//! to the input, which gives 24 dB per octave slope and resonance peaking up
+//! to self-oscillation. The stages use trapezoidal integration with the
+//! feedback loop solved ahead, so the filter stays in tune across the whole
+//! range. The input of the ladder is saturated by a cheap tanh
+//! approximation, giving the filter its characteristic overdrive and
+//! keeping the self-oscillation bounded.
+
+use crate::one_pole;
+use crate::saturation;
+
+// Feedback gain of 4.0 places the filter on the edge of stability. Going
+// slightly above makes sure it starts oscillating on its own.
+const MAX_FEEDBACK: f32 = 4.2;
+
+/// Yields filtered signal.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct LadderFilter {
+    sample_rate: u32,
+    g: f32,
+    feedback: f32,
+    drive: f32,
+    state: [f32; 4],
+}
+
+impl LadderFilter {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut filter = Self {
+            sample_rate,
+            g: 0.0,
+            feedback: 0.0,
+            drive: 1.0,
+            state: [0.0; 4],
+        };
+        filter.set_frequency(0.0);
+        filter
+    }
+
+    /// Set the cutoff frequency.
+    ///
+    /// It gets clamped just below the Nyquist frequency.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.g = one_pole::integrator_gain(self.sample_rate, frequency);
+        self
+    }
+
+    /// Set resonance between 0.0 and 1.0. The filter self-oscillates on the
+    /// cutoff frequency when the resonance is close to 1.0.
+    pub fn set_resonance(&mut self, resonance: f32) -> &mut Self {
+        self.feedback = resonance.clamp(0.0, 1.0) * MAX_FEEDBACK;
+        self
+    }
+
+    /// Set gain applied on the input before saturation. Values above 1.0
+    /// overdrive the filter.
+    pub fn set_drive(&mut self, drive: f32) -> &mut Self {
+        self.drive = f32::max(drive, 0.0);
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    // Vadim Zavalishin, The Art of VA Filter Design, chapter 5
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let g = self.g;
+        let input = value * self.drive;
+
+        // Each stage outputs g * x + (1 - g) * s. Chaining them gives the
+        // output of the ladder as g^4 * x plus contribution of the state.
+        let contribution = self
+            .state
+            .iter()
+            .fold(0.0, |contribution, s| contribution * g + (1.0 - g) * s);
+        let g4 = g * g * g * g;
+        let output = (g4 * input + contribution) / (1.0 + self.feedback * g4);
+
+        let mut x = saturation::tanh(input - self.feedback * output);
+        for s in self.state.iter_mut() {
+            let v = (x - *s) * g;
+            let y = v + *s;
+            *s = y + v;
+            x = y;
+        }
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+    use crate::test_utils::noise;
+
+    const SAMPLE_RATE: u32 = 1024;
+
+    // Analyze the response of the filter to a quiet impulse, keeping the
+    // saturation in its linear region.
+    fn impulse_response(filter: &mut LadderFilter) -> SpectralAnalysis {
+        let mut signal = [0.0; 1024];
+        signal[0] = 0.01;
+        filter.process(&mut signal);
+        SpectralAnalysis::analyze(&signal, SAMPLE_RATE)
+    }
+
+    #[test]
+    fn initialize_filter() {
+        const SAMPLE_RATE: u32 = 1;
+        let _filter = LadderFilter::new(SAMPLE_RATE);
+    }
+
+    #[test]
+    fn low_pass() {
+        let mut signal = noise();
+
+        let mut filter = LadderFilter::new(SAMPLE_RATE);
+        filter.set_frequency(100.0);
+        filter.process(&mut signal);
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        let low_mean_magnitude = analysis.mean_magnitude(0.0, 100.0);
+        let high_mean_magnitude = analysis.mean_magnitude(100.0, 600.0);
+
+        assert!(low_mean_magnitude / high_mean_magnitude > 10.0);
+    }
+
+    #[test]
+    fn attenuate_by_12_db_on_cutoff_without_resonance() {
+        for frequency in [20.0, 100.0, 300.0, 450.0] {
+            let mut filter = LadderFilter::new(SAMPLE_RATE);
+            filter.set_frequency(frequency);
+            let analysis = impulse_response(&mut filter);
+
+            let ratio = analysis.magnitude(frequency) / analysis.magnitude(0.0);
+            assert_relative_eq!(ratio, 1.0 / 16.0, epsilon = 0.005);
+        }
+    }
+
+    #[test]
+    fn resonance_boosts_cutoff() {
+        let mut filter = LadderFilter::new(SAMPLE_RATE);
+        filter.set_frequency(200.0).set_resonance(0.9);
+        let analysis = impulse_response(&mut filter);
+
+        assert_relative_eq!(analysis.strongest_peak(), 200.0, epsilon = 2.0);
+    }
+
+    #[test]
+    fn self_oscillate_on_cutoff() {
+        for frequency in [50.0, 100.0, 200.0] {
+            let mut filter = LadderFilter::new(SAMPLE_RATE);
+            filter.set_frequency(frequency).set_resonance(1.0);
+
+            let mut signal = [0.0; 1024];
+            signal[0] = 0.01;
+            filter.process(&mut signal);
+            let mut signal = [0.0; 1024];
+            filter.process(&mut signal);
+
+            let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+            assert_relative_eq!(analysis.strongest_peak(), frequency, epsilon = 5.0);
+            let peak = signal
+                .iter()
+                .fold(0.0, |max, x| f32::max(max, f32::abs(*x)));
+            assert!(peak > 0.1);
+        }
+    }
+
+    #[test]
+    fn stay_bounded_when_overdriven() {
+        let mut signal = noise();
+
+        let mut filter = LadderFilter::new(SAMPLE_RATE);
+        filter
+            .set_frequency(500.0)
+            .set_resonance(1.0)
+            .set_drive(10.0);
+        filter.process(&mut signal);
+
+        assert!(signal.iter().all(|x| x.is_finite() && f32::abs(*x) < 2.0));
+    }
+}
@@ -0,0 +1,343 @@
+//! Low-frequency oscillator used as a source of modulation.
+//!
+//! The oscillator offers multiple shapes, bipolar or unipolar output, phase
+//! reset through `reset` or a sync signal passed to `synced`, and rates set
+//! either in hz or synchronized to tempo.
+//!
+//! # Example
+//!
+//! ```rust
+//! use sirena::lfo::{Lfo, Triangle, Unipolar};
+//! use sirena::signal::Signal;
+//!
+//! let mut lfo = Lfo::new(48000);
+//! lfo.set_shape(Triangle)
+//!     .set_polarity(Unipolar)
+//!     .set_tempo(120.0, 0.5);
+//!
+//! let modulation = lfo.next();
+//! assert!((0.0..=1.0).contains(&modulation));
+//! ```
+
+use crate::signal::{wrap, Signal, Waveform, XorShift};
+
+/// Sync signal above this value is considered high.
+pub const SYNC_THRESHOLD: f32 = 0.5;
+
+/// Yields low-frequency modulation signal.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct Lfo {
+    sample_rate: u32,
+    shape: Shape,
+    polarity: Polarity,
+    phase: f32,
+    step: f32,
+    rng: XorShift,
+    random_start: f32,
+    random_target: f32,
+}
+
+impl Lfo {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut rng = XorShift::new(1);
+        let random_target = rng.next_bipolar();
+        Self {
+            sample_rate,
+            shape: Sine,
+            polarity: Bipolar,
+            phase: 0.0,
+            step: 0.0,
+            rng,
+            random_start: 0.0,
+            random_target,
+        }
+    }
+
+    pub fn set_shape(&mut self, shape: Shape) -> &mut Self {
+        self.shape = shape;
+        self
+    }
+
+    pub fn set_polarity(&mut self, polarity: Polarity) -> &mut Self {
+        self.polarity = polarity;
+        self
+    }
+
+    /// Set frequency in hz. Negative frequency runs the cycle backwards.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.step = frequency / self.sample_rate as f32;
+        self
+    }
+
+    /// Seed the generator of the random shape. Oscillators with different
+    /// seeds yield different random sequences. The seed is 1 by default.
+    pub fn set_seed(&mut self, seed: u32) -> &mut Self {
+        // Continue from the current value to prevent jumps.
+        self.random_start = self.random_value();
+        self.rng = XorShift::new(seed);
+        self.random_target = self.rng.next_bipolar();
+        self
+    }
+
+    /// Synchronize the rate to tempo given in beats per minute.
+    ///
+    /// The `beats` set the length of a single cycle in beats (quarter
+    /// notes). For example, `1.0` cycles every quarter note, `0.5` every
+    /// eighth note, `2.0 / 3.0` every quarter note triplet and `4.0` every
+    /// bar of 4/4.
+    pub fn set_tempo(&mut self, bpm: f32, beats: f32) -> &mut Self {
+        self.set_frequency(bpm / 60.0 / beats)
+    }
+
+    /// Reset the phase to the start of the cycle.
+    pub fn reset(&mut self) {
+        // Continue the random shape from its current value to prevent jumps.
+        self.random_start = self.random_value();
+        self.random_target = self.rng.next_bipolar();
+        self.phase = 0.0;
+    }
+
+    /// Reset the phase of the oscillator on each rising edge of the given
+    /// sync signal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::lfo::{Lfo, Ramp};
+    /// use sirena::signal::{self, Signal};
+    ///
+    /// let sync = signal::from_iter([0.0, 0.0, 1.0]);
+    /// let mut lfo = Lfo::new(4);
+    /// lfo.set_shape(Ramp).set_frequency(1.0);
+    ///
+    /// let mut lfo = lfo.synced(sync);
+    /// assert_eq!(lfo.next(), -1.0);
+    /// assert_eq!(lfo.next(), -0.5);
+    /// assert_eq!(lfo.next(), -1.0);
+    /// ```
+    pub fn synced<S>(self, sync: S) -> Synced<S>
+    where
+        S: Signal,
+    {
+        Synced {
+            lfo: self,
+            sync,
+            high: false,
+        }
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        // Modulation does not need to be band-limited, so the waveforms are
+        // sampled with zero step.
+        let value = match self.shape {
+            Sine => Waveform::Sine.sample(self.phase, 0.0),
+            Triangle => Waveform::Triangle.sample(self.phase, 0.0),
+            Ramp => Waveform::Saw.sample(self.phase, 0.0),
+            Square => Waveform::Square.sample(self.phase, 0.0),
+            Random => self.random_value(),
+        };
+
+        // Pick a new random value on the side of the cycle being entered, so
+        // the random shape stays smooth when running backwards too.
+        self.phase += self.step;
+        if self.phase >= 1.0 {
+            self.random_start = self.random_target;
+            self.random_target = self.rng.next_bipolar();
+        } else if self.phase < 0.0 {
+            self.random_target = self.random_start;
+            self.random_start = self.rng.next_bipolar();
+        }
+        self.phase = wrap(self.phase);
+
+        match self.polarity {
+            Bipolar => value,
+            Unipolar => (value + 1.0) * 0.5,
+        }
+    }
+
+    // Smoothly interpolate between random values picked on each cycle.
+    fn random_value(&self) -> f32 {
+        let x = self.phase;
+        let smooth = x * x * (3.0 - 2.0 * x);
+        self.random_start + (self.random_target - self.random_start) * smooth
+    }
+}
+
+impl Signal for Lfo {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.tick()
+    }
+}
+
+/// Oscillator with phase reset by a sync signal.
+pub struct Synced<S>
+where
+    S: Signal,
+{
+    lfo: Lfo,
+    sync: S,
+    high: bool,
+}
+
+impl<S> Synced<S>
+where
+    S: Signal,
+{
+    /// Access the inner oscillator to change its parameters.
+    pub fn lfo_mut(&mut self) -> &mut Lfo {
+        &mut self.lfo
+    }
+}
+
+impl<S> Signal for Synced<S>
+where
+    S: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let high = self.sync.next() > SYNC_THRESHOLD;
+        if high && !self.high {
+            self.lfo.reset();
+        }
+        self.high = high;
+        self.lfo.tick()
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.sync.is_exhausted()
+    }
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Shape {
+    Sine,
+    Triangle,
+    /// Rising sawtooth.
+    Ramp,
+    Square,
+    /// Random values picked on each cycle, smoothly interpolated.
+    Random,
+}
+
+pub use Shape::*;
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Polarity {
+    /// Output between -1.0 and 1.0.
+    Bipolar,
+    /// Output between 0.0 and 1.0.
+    Unipolar,
+}
+
+pub use Polarity::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+
+    const SAMPLE_RATE: u32 = 1024;
+
+    fn strongest_frequency(lfo: &mut Lfo) -> f32 {
+        let mut signal = [0.0; SAMPLE_RATE as usize];
+        signal.iter_mut().for_each(|x| *x = lfo.tick());
+        SpectralAnalysis::analyze(&signal, SAMPLE_RATE).strongest_peak()
+    }
+
+    #[test]
+    fn oscillate_at_given_frequency() {
+        for shape in [Sine, Triangle, Ramp, Square] {
+            let mut lfo = Lfo::new(SAMPLE_RATE);
+            lfo.set_shape(shape).set_frequency(8.0);
+            assert_relative_eq!(strongest_frequency(&mut lfo), 8.0);
+        }
+    }
+
+    #[test]
+    fn oscillate_at_negative_frequency() {
+        for shape in [Sine, Triangle, Ramp, Square] {
+            let mut lfo = Lfo::new(SAMPLE_RATE);
+            lfo.set_shape(shape).set_frequency(-8.0);
+            assert_relative_eq!(strongest_frequency(&mut lfo), 8.0);
+        }
+    }
+
+    #[test]
+    fn oscillate_at_tempo() {
+        let mut lfo = Lfo::new(SAMPLE_RATE);
+        lfo.set_tempo(120.0, 0.25);
+        assert_relative_eq!(strongest_frequency(&mut lfo), 8.0);
+    }
+
+    #[test]
+    fn keep_bipolar_output_within_range() {
+        for shape in [Sine, Triangle, Ramp, Square, Random] {
+            for frequency in [10.0, -10.0] {
+                let mut lfo = Lfo::new(SAMPLE_RATE);
+                lfo.set_shape(shape).set_frequency(frequency);
+                for _ in 0..1000 {
+                    let x = lfo.tick();
+                    assert!((-1.0..=1.0).contains(&x));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn keep_unipolar_output_within_range() {
+        for shape in [Sine, Triangle, Ramp, Square, Random] {
+            let mut lfo = Lfo::new(SAMPLE_RATE);
+            lfo.set_shape(shape)
+                .set_polarity(Unipolar)
+                .set_frequency(10.0);
+            for _ in 0..1000 {
+                let x = lfo.tick();
+                assert!((0.0..=1.0).contains(&x));
+            }
+        }
+    }
+
+    #[test]
+    fn change_random_shape_smoothly() {
+        for frequency in [10.0, -10.0] {
+            let mut lfo = Lfo::new(SAMPLE_RATE);
+            lfo.set_shape(Random).set_frequency(frequency);
+            let mut previous = lfo.tick();
+            for _ in 0..1000 {
+                let x = lfo.tick();
+                assert!(f32::abs(x - previous) < 0.05);
+                previous = x;
+            }
+        }
+    }
+
+    #[test]
+    fn yield_random_sequence_given_by_seed() {
+        let random = |seed| {
+            let mut lfo = Lfo::new(SAMPLE_RATE);
+            lfo.set_shape(Random).set_frequency(100.0).set_seed(seed);
+            let mut signal = [0.0; 100];
+            signal.iter_mut().for_each(|x| *x = lfo.tick());
+            signal
+        };
+
+        assert_eq!(random(7), random(7));
+        assert_ne!(random(7), random(8));
+    }
+
+    #[test]
+    fn restart_cycle_on_reset() {
+        let mut lfo = Lfo::new(SAMPLE_RATE);
+        lfo.set_shape(Ramp).set_frequency(10.0);
+        for _ in 0..30 {
+            lfo.tick();
+        }
+        lfo.reset();
+        assert_relative_eq!(lfo.tick(), -1.0);
+    }
+}
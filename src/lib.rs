@@ -4,8 +4,25 @@
 #[macro_use]
 extern crate approx;
 
+pub mod allpass;
+pub mod biquad;
+pub mod cascaded_filter;
+pub mod comb;
+pub mod crossover;
+pub mod delay;
+pub mod envelope;
+pub mod ladder_filter;
+pub mod lfo;
 pub mod memory_manager;
+pub mod one_pole;
 pub mod ring_buffer;
+pub mod saturation;
 pub mod signal;
+pub mod smoothing;
 pub mod spectral_analysis;
 pub mod state_variable_filter;
+pub mod stereo_signal;
+#[cfg(test)]
+pub(crate) mod test_utils;
+pub mod wavetable;
+pub mod zdf_state_variable_filter;
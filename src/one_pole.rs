@@ -0,0 +1,270 @@
+//! One-pole low and high pass filters and DC blocker.
+//!
+//! These filters have a gentle slope of 6 dB per octave. They are cheap, so
+//! they can be used to tame harshness of a waveshaper, smoothen control
+//! signals, or remove DC offset of a signal.
+
+use core::f32::consts::PI;
+
+/// Yields low pass filtered signal.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct LowPass {
+    integrator: Integrator,
+}
+
+impl LowPass {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            integrator: Integrator::new(sample_rate),
+        }
+    }
+
+    /// Set the cutoff frequency.
+    ///
+    /// It gets clamped just below the Nyquist frequency.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.integrator.set_frequency(frequency);
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        self.integrator.tick(value)
+    }
+}
+
+/// Yields high pass filtered signal.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct HighPass {
+    integrator: Integrator,
+}
+
+impl HighPass {
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            integrator: Integrator::new(sample_rate),
+        }
+    }
+
+    /// Set the cutoff frequency.
+    ///
+    /// It gets clamped just below the Nyquist frequency.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.integrator.set_frequency(frequency);
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        value - self.integrator.tick(value)
+    }
+}
+
+/// Removes DC offset from the signal.
+///
+/// Compared to [`HighPass`], this filter is cheaper to update and is meant
+/// for cutoff frequencies far below the audible range.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct DcBlocker {
+    sample_rate: u32,
+    r: f32,
+    x1: f32,
+    y1: f32,
+}
+
+impl DcBlocker {
+    /// Initialize the filter with cutoff at 10 Hz.
+    pub fn new(sample_rate: u32) -> Self {
+        let mut filter = Self {
+            sample_rate,
+            r: 0.0,
+            x1: 0.0,
+            y1: 0.0,
+        };
+        filter.set_frequency(10.0);
+        filter
+    }
+
+    /// Set the cutoff frequency.
+    ///
+    /// The approximation used to calculate the pole is accurate only for
+    /// frequencies much lower than the sample rate.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.r = f32::max(1.0 - 2.0 * PI * frequency / self.sample_rate as f32, 0.0);
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let y = value - self.x1 + self.r * self.y1;
+        self.x1 = value;
+        self.y1 = y;
+        y
+    }
+}
+
+// One-pole low pass using trapezoidal integration, so it stays accurately
+// tuned up to the Nyquist frequency.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+struct Integrator {
+    sample_rate: u32,
+    g: f32,
+    state: f32,
+}
+
+impl Integrator {
+    fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            g: 0.0,
+            state: 0.0,
+        }
+    }
+
+    fn set_frequency(&mut self, frequency: f32) {
+        self.g = integrator_gain(self.sample_rate, frequency);
+    }
+
+    fn tick(&mut self, value: f32) -> f32 {
+        let v = (value - self.state) * self.g;
+        let y = v + self.state;
+        self.state = y + v;
+        y
+    }
+}
+
+// Gain of the trapezoidal integrator with the cutoff frequency prewarped,
+// clamped just below the Nyquist frequency.
+pub(crate) fn integrator_gain(sample_rate: u32, frequency: f32) -> f32 {
+    let nyquist = sample_rate as f32 / 2.0;
+    let frequency = frequency.clamp(0.0, nyquist * 0.999);
+    let g = libm::tanf(PI * frequency / sample_rate as f32);
+    g / (1.0 + g)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+    use crate::test_utils::{impulse, noise};
+
+    const SAMPLE_RATE: u32 = 1024;
+
+    #[test]
+    fn initialize_filters() {
+        const SAMPLE_RATE: u32 = 1;
+        let _low_pass = LowPass::new(SAMPLE_RATE);
+        let _high_pass = HighPass::new(SAMPLE_RATE);
+        let _dc_blocker = DcBlocker::new(SAMPLE_RATE);
+    }
+
+    #[test]
+    fn low_pass() {
+        let mut signal = noise();
+
+        let mut filter = LowPass::new(SAMPLE_RATE);
+        filter.set_frequency(100.0);
+        filter.process(&mut signal);
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        let low_mean_magnitude = analysis.mean_magnitude(0.0, 100.0);
+        let high_mean_magnitude = analysis.mean_magnitude(100.0, 600.0);
+
+        assert!(low_mean_magnitude / high_mean_magnitude > 3.0);
+    }
+
+    #[test]
+    fn high_pass() {
+        let mut signal = noise();
+
+        let mut filter = HighPass::new(SAMPLE_RATE);
+        filter.set_frequency(100.0);
+        filter.process(&mut signal);
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        let low_mean_magnitude = analysis.mean_magnitude(0.0, 50.0);
+        let high_mean_magnitude = analysis.mean_magnitude(100.0, 600.0);
+
+        assert!(high_mean_magnitude / low_mean_magnitude > 3.0);
+    }
+
+    #[test]
+    fn low_and_high_pass_attenuate_by_3_db_on_cutoff() {
+        for frequency in [20.0, 100.0, 300.0, 500.0] {
+            let mut signal = impulse();
+            let mut filter = LowPass::new(SAMPLE_RATE);
+            filter.set_frequency(frequency);
+            filter.process(&mut signal);
+            let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+            assert_relative_eq!(analysis.magnitude(frequency), 0.5, epsilon = 0.01);
+
+            let mut signal = impulse();
+            let mut filter = HighPass::new(SAMPLE_RATE);
+            filter.set_frequency(frequency);
+            filter.process(&mut signal);
+            let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+            assert_relative_eq!(analysis.magnitude(frequency), 0.5, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn dc_blocker_removes_offset() {
+        let mut signal = [1.0; 1024];
+
+        let mut filter = DcBlocker::new(SAMPLE_RATE);
+        filter.process(&mut signal);
+
+        assert!(f32::abs(signal[1023]) < 0.001);
+    }
+
+    #[test]
+    fn dc_blocker_passes_audible_frequencies() {
+        let mut signal = impulse();
+
+        let mut filter = DcBlocker::new(SAMPLE_RATE);
+        filter.set_frequency(5.0);
+        filter.process(&mut signal);
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        assert!(analysis.magnitude(0.0) < 0.01);
+        assert_relative_eq!(analysis.magnitude(5.0), 0.5, epsilon = 0.05);
+        assert_relative_eq!(analysis.mean_magnitude(50.0, 500.0), 1.0, epsilon = 0.05);
+    }
+}
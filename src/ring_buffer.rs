@@ -1,4 +1,10 @@
 //! Ring buffer is useful for storing of last N samples.
+//!
+//! [`RingBuffer`] stores its samples inline, which suits short buffers.
+//! Longer buffers, e.g. for delays of several seconds, can use
+//! [`SliceRingBuffer`] over a slice allocated by
+//! [`MemoryManager`](crate::memory_manager::MemoryManager). Both implement
+//! the [`Buffer`] trait, so components can be generic over the storage.
 
 #[allow(unused_imports)]
 use micromath::F32Ext;
@@ -29,28 +35,53 @@ impl<const N: usize> RingBuffer<N> {
     }
 
     pub fn write(&mut self, value: f32) {
-        self.write_index %= N;
-        self.buffer[self.write_index] = value;
-        self.write_index += 1;
+        self.write_index = write(&mut self.buffer, self.write_index, value);
     }
 
     pub fn peek(&self, relative_index: i32) -> f32 {
-        let index =
-            (self.write_index as i32 + relative_index - 1).wrapping_rem_euclid(N as i32) as usize;
-        self.buffer[index]
+        peek(&self.buffer, self.write_index, relative_index)
     }
 
+    /// Read a sample between two written samples using linear
+    /// interpolation.
     pub fn peek_interpolated(&self, relative_index: f32) -> f32 {
-        let index_a = relative_index.floor() as i32;
-        let a = self.peek(index_a);
+        Buffer::peek_interpolated(self, relative_index)
+    }
+
+    /// Read a sample between written samples using the given interpolator.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate approx;
+    /// # fn main() {
+    /// use sirena::ring_buffer::{Hermite, RingBuffer};
+    ///
+    /// let mut buffer = RingBuffer::<8>::new();
+    /// for x in [0.0, 1.0, 4.0, 9.0, 16.0] {
+    ///     buffer.write(x);
+    /// }
+    ///
+    /// assert_relative_eq!(buffer.peek_with(-2.5, &mut Hermite), 2.25);
+    /// # }
+    /// ```
+    pub fn peek_with<I: Interpolator>(&self, relative_index: f32, interpolator: &mut I) -> f32 {
+        Buffer::peek_with(self, relative_index, interpolator)
+    }
+}
 
-        let index_b = relative_index.ceil() as i32;
-        let b = self.peek(index_b);
+impl<const N: usize> Buffer for RingBuffer<N> {
+    fn write(&mut self, value: f32) {
+        RingBuffer::write(self, value);
+    }
 
-        let diff = b - a;
-        let root = if relative_index < 0.0 { b } else { a };
+    fn peek(&self, relative_index: i32) -> f32 {
+        RingBuffer::peek(self, relative_index)
+    }
 
-        root + diff * relative_index.fract()
+    fn capacity(&self) -> usize {
+        N
     }
 }
 
@@ -60,9 +91,246 @@ impl<const N: usize> Default for RingBuffer<N> {
     }
 }
 
+/// Write to and read from a ring buffer stored in an external slice.
+///
+/// # Example
+///
+/// ```
+/// use core::mem::MaybeUninit;
+/// use sirena::memory_manager::MemoryManager;
+/// use sirena::ring_buffer::{Buffer, SliceRingBuffer};
+///
+/// static mut MEMORY: [MaybeUninit<u32>; 48000] = unsafe { MaybeUninit::uninit().assume_init() };
+/// let mut memory_manager = MemoryManager::from(unsafe { &mut MEMORY[..] });
+///
+/// let mut buffer = SliceRingBuffer::from(memory_manager.allocate(48000).unwrap());
+/// buffer.write(1.0);
+/// buffer.write(2.0);
+///
+/// assert_eq!(buffer.peek(-1), 1.0);
+/// ```
+pub struct SliceRingBuffer<'a> {
+    buffer: &'a mut [f32],
+    write_index: usize,
+}
+
+impl<'a> SliceRingBuffer<'a> {
+    /// Wrap the given slice. Its current content is kept as if it was
+    /// written before.
+    ///
+    /// # Panics
+    ///
+    /// The slice must not be empty.
+    pub fn new(buffer: &'a mut [f32]) -> Self {
+        assert!(!buffer.is_empty());
+        Self {
+            buffer,
+            write_index: 0,
+        }
+    }
+}
+
+impl<'a> From<&'a mut [f32]> for SliceRingBuffer<'a> {
+    fn from(buffer: &'a mut [f32]) -> Self {
+        Self::new(buffer)
+    }
+}
+
+impl Buffer for SliceRingBuffer<'_> {
+    fn write(&mut self, value: f32) {
+        self.write_index = write(self.buffer, self.write_index, value);
+    }
+
+    fn peek(&self, relative_index: i32) -> f32 {
+        peek(self.buffer, self.write_index, relative_index)
+    }
+
+    fn capacity(&self) -> usize {
+        self.buffer.len()
+    }
+}
+
+/// Common interface of ring buffers.
+pub trait Buffer {
+    /// Write a sample, overwriting the oldest one.
+    fn write(&mut self, value: f32);
+
+    /// Read a written sample. Index 0 points to the last written sample,
+    /// negative indexes to older ones.
+    fn peek(&self, relative_index: i32) -> f32;
+
+    /// Number of samples the buffer can hold.
+    fn capacity(&self) -> usize;
+
+    /// Read a sample between two written samples using linear
+    /// interpolation.
+    fn peek_interpolated(&self, relative_index: f32) -> f32 {
+        self.peek_with(relative_index, &mut Linear)
+    }
+
+    /// Read a sample between written samples using the given interpolator.
+    fn peek_with<I: Interpolator>(&self, relative_index: f32, interpolator: &mut I) -> f32 {
+        interpolator.interpolate(|index| self.peek(index), relative_index)
+    }
+}
+
+fn write(buffer: &mut [f32], write_index: usize, value: f32) -> usize {
+    let write_index = write_index % buffer.len();
+    buffer[write_index] = value;
+    write_index + 1
+}
+
+fn peek(buffer: &[f32], write_index: usize, relative_index: i32) -> f32 {
+    let length = buffer.len() as i32;
+    let index = (write_index as i32 + relative_index - 1).wrapping_rem_euclid(length) as usize;
+    buffer[index]
+}
+
+/// Interpolation between samples stored in a buffer.
+///
+/// Interpolators reading more than two points need samples on both sides of
+/// the read position. Keep the relative index at `1.0 - MIN_DELAY` or lower,
+/// so they do not reach past the last written sample.
+pub trait Interpolator {
+    /// The shortest delay in samples, counted from the next sample to be
+    /// written, the interpolator can read at.
+    const MIN_DELAY: f32 = 1.0;
+
+    /// Interpolate value at fractional `relative_index`, reading samples
+    /// through `peek`.
+    fn interpolate<P>(&mut self, peek: P, relative_index: f32) -> f32
+    where
+        P: Fn(i32) -> f32;
+}
+
+/// Linear interpolation between two neighbouring samples.
+///
+/// It is cheap, but attenuates high frequencies when reading between
+/// samples.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Linear;
+
+impl Interpolator for Linear {
+    fn interpolate<P>(&mut self, peek: P, relative_index: f32) -> f32
+    where
+        P: Fn(i32) -> f32,
+    {
+        let index = relative_index.floor();
+        let t = relative_index - index;
+        let a = peek(index as i32);
+        let b = peek(index as i32 + 1);
+        a + (b - a) * t
+    }
+}
+
+/// Cubic Hermite (Catmull-Rom) interpolation over four samples.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Hermite;
+
+impl Interpolator for Hermite {
+    const MIN_DELAY: f32 = 2.0;
+
+    fn interpolate<P>(&mut self, peek: P, relative_index: f32) -> f32
+    where
+        P: Fn(i32) -> f32,
+    {
+        let index = relative_index.floor();
+        let t = relative_index - index;
+        let [y0, y1, y2, y3] = four_points(peek, index as i32);
+
+        let c1 = 0.5 * (y2 - y0);
+        let c2 = y0 - 2.5 * y1 + 2.0 * y2 - 0.5 * y3;
+        let c3 = 0.5 * (y3 - y0) + 1.5 * (y1 - y2);
+        ((c3 * t + c2) * t + c1) * t + y1
+    }
+}
+
+/// Third-order Lagrange interpolation over four samples.
+///
+/// It reproduces cubic polynomials exactly.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lagrange;
+
+impl Interpolator for Lagrange {
+    const MIN_DELAY: f32 = 2.0;
+
+    fn interpolate<P>(&mut self, peek: P, relative_index: f32) -> f32
+    where
+        P: Fn(i32) -> f32,
+    {
+        let index = relative_index.floor();
+        let t = relative_index - index;
+        let [y0, y1, y2, y3] = four_points(peek, index as i32);
+
+        let a = t + 1.0;
+        let b = t - 1.0;
+        let c = t - 2.0;
+        -t * b * c / 6.0 * y0 + a * b * c / 2.0 * y1 - a * t * c / 2.0 * y2 + a * t * b / 6.0 * y3
+    }
+}
+
+/// First-order all-pass interpolation.
+///
+/// It keeps magnitude of all frequencies, but it carries state. Each
+/// instance must be used for a single read position, advanced once per
+/// written sample. Jumps of the position cause transients.
+///
+/// The fractional delay is kept between 0.1 and 1.1 samples, moving to the
+/// next newer pair of samples when needed, so the filter pole stays away
+/// from -1 and it does not ring close to whole samples. It therefore needs
+/// a delay of at least two samples.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AllPass {
+    previous: f32,
+}
+
+impl AllPass {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Interpolator for AllPass {
+    const MIN_DELAY: f32 = 2.0;
+
+    // https://ccrma.stanford.edu/~jos/pasp/First_Order_Allpass_Interpolation.html
+    fn interpolate<P>(&mut self, peek: P, relative_index: f32) -> f32
+    where
+        P: Fn(i32) -> f32,
+    {
+        let mut index = relative_index.floor();
+        let mut delay = 1.0 - (relative_index - index);
+        if delay < 0.1 {
+            index += 1.0;
+            delay += 1.0;
+        }
+        let eta = (1.0 - delay) / (1.0 + delay);
+        let older = peek(index as i32);
+        let newer = peek(index as i32 + 1);
+        self.previous = eta * newer + older - eta * self.previous;
+        self.previous
+    }
+}
+
+fn four_points<P>(peek: P, index: i32) -> [f32; 4]
+where
+    P: Fn(i32) -> f32,
+{
+    [
+        peek(index - 1),
+        peek(index),
+        peek(index + 1),
+        peek(index + 2),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::memory_manager::MemoryManager;
+    use crate::spectral_analysis::SpectralAnalysis;
+    use core::f32::consts::PI;
+    use core::mem::MaybeUninit;
 
     #[test]
     fn initialize_buffer() {
@@ -118,4 +386,154 @@ mod tests {
         assert_eq!(buffer.peek(0) as usize, 100);
         assert_eq!(buffer.peek(-1) as usize, 100 - 1);
     }
+
+    // Fill the buffer with values of the given function, so that the last
+    // written sample is f(0.0) and older samples are on negative positions.
+    fn buffer_from_fn(f: impl Fn(f32) -> f32) -> RingBuffer<16> {
+        let mut buffer = RingBuffer::<16>::new();
+        for i in -15..=0 {
+            buffer.write(f(i as f32));
+        }
+        buffer
+    }
+
+    #[test]
+    fn linear_interpolation_reproduces_line() {
+        let line = |x: f32| 2.0 * x - 3.0;
+        let buffer = buffer_from_fn(line);
+
+        for position in [-1.0, -1.3, -5.5, -10.9] {
+            assert_relative_eq!(
+                buffer.peek_with(position, &mut Linear),
+                line(position),
+                epsilon = 0.0001
+            );
+        }
+    }
+
+    #[test]
+    fn hermite_interpolation_reproduces_quadratic_polynomial() {
+        let quadratic = |x: f32| 0.5 * x * x - 2.0 * x + 1.0;
+        let buffer = buffer_from_fn(quadratic);
+
+        for position in [-1.0, -1.3, -5.5, -10.9] {
+            assert_relative_eq!(
+                buffer.peek_with(position, &mut Hermite),
+                quadratic(position),
+                epsilon = 0.001
+            );
+        }
+    }
+
+    #[test]
+    fn lagrange_interpolation_reproduces_cubic_polynomial() {
+        let cubic = |x: f32| 0.1 * x * x * x + 0.5 * x * x - 2.0 * x + 1.0;
+        let buffer = buffer_from_fn(cubic);
+
+        for position in [-1.0, -1.3, -5.5, -10.9] {
+            assert_relative_eq!(
+                buffer.peek_with(position, &mut Lagrange),
+                cubic(position),
+                epsilon = 0.001
+            );
+        }
+    }
+
+    #[test]
+    fn all_pass_interpolation_delays_ramp() {
+        let mut buffer = RingBuffer::<16>::new();
+        let mut interpolator = AllPass::new();
+
+        let mut value = 0.0;
+        for i in 0..100 {
+            buffer.write(i as f32);
+            value = buffer.peek_with(-5.5, &mut interpolator);
+        }
+
+        assert_relative_eq!(value, 99.0 - 5.5, epsilon = 0.001);
+    }
+
+    #[test]
+    fn all_pass_interpolation_does_not_ring_close_to_whole_samples() {
+        let mut buffer = RingBuffer::<16>::new();
+        let mut interpolator = AllPass::new();
+
+        let mut response = [0.0; 64];
+        for (i, y) in response.iter_mut().enumerate() {
+            buffer.write(if i == 0 { 1.0 } else { 0.0 });
+            *y = buffer.peek_with(-5.01, &mut interpolator);
+        }
+
+        let tail: f32 = response[8..].iter().map(|y| y * y).sum();
+        assert!(tail < 0.0001);
+    }
+
+    // Read a sine through a delay slowly modulated between 2 and 3 samples
+    // and measure how much of its power is kept.
+    fn modulated_sine_power(interpolator: &mut impl Interpolator) -> f32 {
+        const SAMPLE_RATE: u32 = 1024;
+        const FREQUENCY: f32 = 300.0;
+
+        let mut buffer = RingBuffer::<8>::new();
+        let mut input = [0.0; 1024];
+        let mut output = [0.0; 1024];
+        for (i, (x, y)) in input.iter_mut().zip(output.iter_mut()).enumerate() {
+            let phase = i as f32 / SAMPLE_RATE as f32;
+            *x = libm::sinf(2.0 * PI * FREQUENCY * phase);
+            buffer.write(*x);
+            let delay = 2.5 + 0.5 * libm::sinf(2.0 * PI * phase);
+            *y = buffer.peek_with(-delay, interpolator);
+        }
+
+        let input = SpectralAnalysis::analyze(&input, SAMPLE_RATE);
+        let output = SpectralAnalysis::analyze(&output, SAMPLE_RATE);
+        output.mean_magnitude(290.0, 310.0) / input.mean_magnitude(290.0, 310.0)
+    }
+
+    #[test]
+    fn higher_order_interpolation_keeps_power_of_modulated_sine() {
+        assert!(modulated_sine_power(&mut Linear) < 0.75);
+        assert!(modulated_sine_power(&mut Hermite) > 0.8);
+        assert!(modulated_sine_power(&mut Lagrange) > 0.8);
+        assert!(modulated_sine_power(&mut AllPass::new()) > 0.95);
+    }
+
+    #[test]
+    fn slice_buffer_shares_behaviour_with_array_buffer() {
+        let mut memory = [0.0; 5];
+        let mut slice_buffer = SliceRingBuffer::from(&mut memory[..]);
+        let mut array_buffer = RingBuffer::<5>::new();
+
+        for x in 0..12 {
+            slice_buffer.write(x as f32);
+            array_buffer.write(x as f32);
+        }
+
+        for index in -6..=1 {
+            assert_eq!(slice_buffer.peek(index), array_buffer.peek(index));
+        }
+        for index in [-0.5, -1.3, -3.9] {
+            assert_relative_eq!(
+                slice_buffer.peek_interpolated(index),
+                array_buffer.peek_interpolated(index)
+            );
+        }
+        assert_eq!(slice_buffer.capacity(), array_buffer.capacity());
+    }
+
+    #[test]
+    fn slice_buffer_wraps_allocated_memory() {
+        static mut MEMORY: [MaybeUninit<u32>; 4] = unsafe { MaybeUninit::uninit().assume_init() };
+        let mut memory_manager = MemoryManager::from(unsafe { &mut MEMORY[..] });
+
+        let mut buffer = SliceRingBuffer::from(memory_manager.allocate(3).unwrap());
+        buffer.write(1.0);
+        buffer.write(2.0);
+        buffer.write(3.0);
+        buffer.write(4.0);
+
+        assert_eq!(buffer.peek(0), 4.0);
+        assert_eq!(buffer.peek(-2), 2.0);
+        assert_eq!(buffer.peek(-3), 4.0);
+    }
 }
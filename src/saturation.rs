@@ -0,0 +1,60 @@
+//! Cheap saturation curves for use in audio rate processing.
+//!
+//! These avoid calls to transcendental functions, so they are fast enough
+//! to be called multiple times per sample on Cortex-M.
+
+/// Approximate hyperbolic tangent using a Padé approximant.
+///
+/// Input is clamped to range between -3.0 and 3.0, where the approximation
+/// reaches exactly -1.0 and 1.0, so the output never leaves this range.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate approx;
+/// # fn main() {
+/// use sirena::saturation;
+/// assert_relative_eq!(saturation::tanh(0.0), 0.0);
+/// assert_relative_eq!(saturation::tanh(0.5), 0.4621, epsilon = 0.01);
+/// assert_relative_eq!(saturation::tanh(10.0), 1.0);
+/// # }
+/// ```
+#[inline]
+pub fn tanh(x: f32) -> f32 {
+    let x = x.clamp(-3.0, 3.0);
+    let x2 = x * x;
+    x * (27.0 + x2) / (27.0 + 9.0 * x2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn approximate_tanh() {
+        for i in -500..=500 {
+            let x = i as f32 / 100.0;
+            assert_relative_eq!(tanh(x), libm::tanhf(x), epsilon = 0.03);
+        }
+    }
+
+    #[test]
+    fn stay_within_bounds() {
+        for i in -500..=500 {
+            let x = i as f32 / 10.0;
+            assert!((-1.0..=1.0).contains(&tanh(x)));
+        }
+    }
+
+    #[test]
+    fn rise_monotonically() {
+        let mut previous = tanh(-10.0);
+        for i in -1000..=1000 {
+            let y = tanh(i as f32 / 100.0);
+            // The curve gets flat near the limits, allow for rounding errors.
+            assert!(y >= previous - f32::EPSILON);
+            previous = y;
+        }
+    }
+}
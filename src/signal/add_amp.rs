@@ -0,0 +1,71 @@
+use super::{Signal, BLOCK_SIZE};
+
+/// Adds samples yielded by signal to samples of given signal.
+pub trait SignalAddAmp: Signal {
+    /// Adds the amplitude of another signal to the signal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalAddAmp};
+    /// let a_frames = [0.5, 2.0, -1.0];
+    /// let b_frames = [1.0, 1.0, 0.5];
+    /// let a_signal = signal::from_iter(a_frames);
+    /// let b_signal = signal::from_iter(b_frames);
+    /// let mut signal = a_signal.add_amp(b_signal);
+    /// assert_eq!(signal.next(), 1.5);
+    /// assert_eq!(signal.next(), 3.0);
+    /// assert_eq!(signal.next(), -0.5);
+    /// ```
+    fn add_amp<O>(self, other: O) -> AddAmp<Self, O>
+    where
+        Self: Sized,
+        O: Signal,
+    {
+        AddAmp {
+            signal: self,
+            other,
+        }
+    }
+}
+
+impl<T> SignalAddAmp for T where T: Signal {}
+
+/// Sums samples yielded by `signal` and `other`.
+#[derive(Clone)]
+pub struct AddAmp<S, O>
+where
+    S: Signal,
+    O: Signal,
+{
+    signal: S,
+    other: O,
+}
+
+impl<S, O> Signal for AddAmp<S, O>
+where
+    S: Signal,
+    O: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.signal.next() + self.other.next()
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted() || self.other.is_exhausted()
+    }
+
+    fn fill(&mut self, buffer: &mut [f32]) {
+        let mut other = [0.0; BLOCK_SIZE];
+        for chunk in buffer.chunks_mut(BLOCK_SIZE) {
+            let other = &mut other[..chunk.len()];
+            self.signal.fill(chunk);
+            self.other.fill(other);
+            for (x, o) in chunk.iter_mut().zip(other.iter()) {
+                *x += *o;
+            }
+        }
+    }
+}
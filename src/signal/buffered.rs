@@ -0,0 +1,121 @@
+use super::{Signal, EQUILIBRIUM};
+use crate::ring_buffer::RingBuffer;
+
+/// Buffer samples yielded by signal to allow peeking ahead.
+pub trait SignalBuffered: Signal {
+    /// Wraps the signal with a buffer of `N` samples, allowing to peek up to
+    /// `N` samples ahead without consuming them.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalBuffered};
+    /// let frames = [0.1, 0.2, 0.3];
+    /// let mut signal = signal::from_iter(frames).buffered::<2>();
+    /// assert_eq!(signal.peek(1), 0.2);
+    /// assert_eq!(signal.peek(0), 0.1);
+    /// assert_eq!(signal.next(), 0.1);
+    /// assert_eq!(signal.peek(0), 0.2);
+    /// assert_eq!(signal.next(), 0.2);
+    /// assert_eq!(signal.next(), 0.3);
+    /// ```
+    fn buffered<const N: usize>(self) -> Buffered<Self, N>
+    where
+        Self: Sized,
+    {
+        Buffered {
+            signal: self,
+            buffer: RingBuffer::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> SignalBuffered for T where T: Signal {}
+
+/// Buffers samples of `signal` ahead, so they can be peeked at.
+pub struct Buffered<S, const N: usize>
+where
+    S: Signal,
+{
+    signal: S,
+    buffer: RingBuffer<N>,
+    len: usize,
+}
+
+impl<S, const N: usize> Buffered<S, N>
+where
+    S: Signal,
+{
+    /// Read the sample `index` positions ahead without consuming it.
+    ///
+    /// Index `0` returns the sample that would be yielded by the following
+    /// call of `next`. Peeking past the end of an exhausted signal returns
+    /// `EQUILIBRIUM`, which is not buffered.
+    ///
+    /// # Panics
+    ///
+    /// The index must be lower than the size of the buffer `N`.
+    pub fn peek(&mut self, index: usize) -> f32 {
+        assert!(index < N);
+        while self.len <= index {
+            if self.signal.is_exhausted() {
+                return EQUILIBRIUM;
+            }
+            self.buffer.write(self.signal.next());
+            self.len += 1;
+        }
+        self.buffer.peek(index as i32 + 1 - self.len as i32)
+    }
+}
+
+impl<S, const N: usize> Signal for Buffered<S, N>
+where
+    S: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        if self.len == 0 {
+            return self.signal.next();
+        }
+        let sample = self.buffer.peek(1 - self.len as i32);
+        self.len -= 1;
+        sample
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.len == 0 && self.signal.is_exhausted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::{from_iter, SignalUntilExhausted};
+
+    #[test]
+    fn keep_order_of_samples_when_peeking_across_buffer_end() {
+        let mut signal = from_iter((0..100).map(|x| x as f32)).buffered::<3>();
+        for x in 0..97 {
+            assert_relative_eq!(signal.peek(2), (x + 2) as f32);
+            assert_relative_eq!(signal.next(), x as f32);
+        }
+    }
+
+    #[test]
+    fn get_exhausted_only_after_emptying_buffer() {
+        let mut signal = from_iter([1.0, 2.0]).buffered::<2>();
+        signal.peek(1);
+        assert!(!signal.is_exhausted());
+        assert_eq!(signal.until_exhausted().count(), 2);
+    }
+
+    #[test]
+    fn do_not_buffer_samples_peeked_past_the_end() {
+        let mut signal = from_iter([1.0]).buffered::<2>();
+        assert_eq!(signal.peek(1), EQUILIBRIUM);
+        assert_eq!(signal.peek(0), 1.0);
+        assert_eq!(signal.until_exhausted().count(), 1);
+    }
+}
@@ -45,4 +45,16 @@ where
     fn next(&mut self) -> f32 {
         self.signal.next().clamp(-self.threshold, self.threshold)
     }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted()
+    }
+
+    fn fill(&mut self, buffer: &mut [f32]) {
+        self.signal.fill(buffer);
+        for x in buffer.iter_mut() {
+            *x = x.clamp(-self.threshold, self.threshold);
+        }
+    }
 }
@@ -31,4 +31,9 @@ impl Signal for Constant {
     fn next(&mut self) -> f32 {
         self.value
     }
+
+    #[inline]
+    fn fill(&mut self, buffer: &mut [f32]) {
+        buffer.fill(self.value);
+    }
 }
@@ -0,0 +1,71 @@
+use super::Signal;
+
+/// Crossfade between signal and given signal.
+pub trait SignalCrossfade: Signal {
+    /// Crossfades between the signal and another signal, reading the ratio
+    /// from the control signal on each tick.
+    ///
+    /// Control of `0.0` yields only the original signal, `1.0` yields only
+    /// the other signal. It gets clamped between these two.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalCrossfade};
+    /// let a_signal = signal::constant(1.0);
+    /// let b_signal = signal::constant(-1.0);
+    /// let control = signal::from_iter([0.0, 0.5, 1.0]);
+    /// let mut signal = a_signal.crossfade(b_signal, control);
+    /// assert_eq!(signal.next(), 1.0);
+    /// assert_eq!(signal.next(), 0.0);
+    /// assert_eq!(signal.next(), -1.0);
+    /// ```
+    fn crossfade<O, C>(self, other: O, control: C) -> Crossfade<Self, O, C>
+    where
+        Self: Sized,
+        O: Signal,
+        C: Signal,
+    {
+        Crossfade {
+            signal: self,
+            other,
+            control,
+        }
+    }
+}
+
+impl<T> SignalCrossfade for T where T: Signal {}
+
+/// Crossfades between samples yielded by `signal` and `other` based on
+/// samples of `control`.
+#[derive(Clone)]
+pub struct Crossfade<S, O, C>
+where
+    S: Signal,
+    O: Signal,
+    C: Signal,
+{
+    signal: S,
+    other: O,
+    control: C,
+}
+
+impl<S, O, C> Signal for Crossfade<S, O, C>
+where
+    S: Signal,
+    O: Signal,
+    C: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let a = self.signal.next();
+        let b = self.other.next();
+        let ratio = self.control.next().clamp(0.0, 1.0);
+        a + (b - a) * ratio
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted() || self.other.is_exhausted() || self.control.is_exhausted()
+    }
+}
@@ -0,0 +1,43 @@
+//! Turn a closure into a signal.
+
+use super::Signal;
+
+/// Create a new `Signal` yielding samples returned by the given closure.
+///
+/// # Example
+///
+/// ```rust
+/// use sirena::signal::{self, Signal};
+/// let mut value = 0.0;
+/// let mut signal = signal::from_fn(|| {
+///     value += 1.0;
+///     value
+/// });
+/// assert_eq!(signal.next(), 1.0);
+/// assert_eq!(signal.next(), 2.0);
+/// ```
+pub fn from_fn<F>(f: F) -> FromFn<F>
+where
+    F: FnMut() -> f32,
+{
+    FromFn { f }
+}
+
+/// A type that wraps a closure and provides a `Signal` implementation for it.
+#[derive(Clone)]
+pub struct FromFn<F>
+where
+    F: FnMut() -> f32,
+{
+    f: F,
+}
+
+impl<F> Signal for FromFn<F>
+where
+    F: FnMut() -> f32,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        (self.f)()
+    }
+}
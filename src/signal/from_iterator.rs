@@ -3,6 +3,20 @@
 use super::{Signal, EQUILIBRIUM};
 
 /// Create a new `Signal` from the given `Frame`-yielding `Iterator`.
+///
+/// Once the iterator runs out, the signal yields `EQUILIBRIUM` and reports
+/// itself as exhausted.
+///
+/// # Example
+///
+/// ```rust
+/// use sirena::signal::{self, Signal};
+/// let frames = [0.1];
+/// let mut signal = signal::from_iter(frames);
+/// assert_eq!(signal.next(), 0.1);
+/// assert!(signal.is_exhausted());
+/// assert_eq!(signal.next(), signal::EQUILIBRIUM);
+/// ```
 pub fn from_iter<I>(frames: I) -> FromIterator<I::IntoIter>
 where
     I: IntoIterator<Item = f32>,
@@ -36,4 +50,9 @@ where
             None => EQUILIBRIUM,
         }
     }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.next.is_none()
+    }
 }
@@ -0,0 +1,64 @@
+use super::Signal;
+
+/// Inspect samples yielded by signal.
+pub trait SignalInspect: Signal {
+    /// Calls the given closure with each sample of the signal, passing the
+    /// sample through unchanged.
+    ///
+    /// This is useful for metering.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalInspect};
+    /// let frames = [0.5, -1.0];
+    /// let mut peak = 0.0;
+    /// let mut signal =
+    ///     signal::from_iter(frames).inspect(|x| peak = f32::max(peak, f32::abs(*x)));
+    /// assert_eq!(signal.next(), 0.5);
+    /// assert_eq!(signal.next(), -1.0);
+    /// drop(signal);
+    /// assert_eq!(peak, 1.0);
+    /// ```
+    fn inspect<F>(self, inspect: F) -> Inspect<Self, F>
+    where
+        Self: Sized,
+        F: FnMut(&f32),
+    {
+        Inspect {
+            signal: self,
+            inspect,
+        }
+    }
+}
+
+impl<T> SignalInspect for T where T: Signal {}
+
+/// Passes samples yielded by `signal` to the `inspect` closure.
+#[derive(Clone)]
+pub struct Inspect<S, F>
+where
+    S: Signal,
+    F: FnMut(&f32),
+{
+    signal: S,
+    inspect: F,
+}
+
+impl<S, F> Signal for Inspect<S, F>
+where
+    S: Signal,
+    F: FnMut(&f32),
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let sample = self.signal.next();
+        (self.inspect)(&sample);
+        sample
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted()
+    }
+}
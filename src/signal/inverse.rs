@@ -0,0 +1,48 @@
+use super::Signal;
+
+/// Invert samples yielded by signal.
+pub trait SignalInverse: Signal {
+    /// Inverts the polarity of the signal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalInverse};
+    /// let frames = [0.5, -1.0];
+    /// let mut signal = signal::from_iter(frames).inverse();
+    /// assert_eq!(signal.next(), -0.5);
+    /// assert_eq!(signal.next(), 1.0);
+    /// ```
+    fn inverse(self) -> Inverse<Self>
+    where
+        Self: Sized,
+    {
+        Inverse { signal: self }
+    }
+}
+
+impl<T> SignalInverse for T where T: Signal {}
+
+/// Inverts polarity of samples yielded by `signal`.
+#[derive(Clone)]
+pub struct Inverse<S>
+where
+    S: Signal,
+{
+    signal: S,
+}
+
+impl<S> Signal for Inverse<S>
+where
+    S: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        -self.signal.next()
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted()
+    }
+}
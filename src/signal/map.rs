@@ -0,0 +1,52 @@
+use super::Signal;
+
+/// Map samples yielded by signal through a closure.
+pub trait SignalMap: Signal {
+    /// Maps each sample of the signal through the given closure.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalMap};
+    /// let frames = [0.5, -1.0];
+    /// let mut signal = signal::from_iter(frames).map(|x| x * x);
+    /// assert_eq!(signal.next(), 0.25);
+    /// assert_eq!(signal.next(), 1.0);
+    /// ```
+    fn map<M>(self, map: M) -> Map<Self, M>
+    where
+        Self: Sized,
+        M: FnMut(f32) -> f32,
+    {
+        Map { signal: self, map }
+    }
+}
+
+impl<T> SignalMap for T where T: Signal {}
+
+/// Maps samples yielded by `signal` through the `map` closure.
+#[derive(Clone)]
+pub struct Map<S, M>
+where
+    S: Signal,
+    M: FnMut(f32) -> f32,
+{
+    signal: S,
+    map: M,
+}
+
+impl<S, M> Signal for Map<S, M>
+where
+    S: Signal,
+    M: FnMut(f32) -> f32,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        (self.map)(self.signal.next())
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted()
+    }
+}
@@ -0,0 +1,65 @@
+use super::Signal;
+
+/// Mix samples yielded by signal with given signal.
+pub trait SignalMix: Signal {
+    /// Mixes the signal with another signal in the given ratio.
+    ///
+    /// Ratio of `0.0` yields only the original signal, `1.0` yields only the
+    /// other signal. It gets clamped between these two.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalMix};
+    /// let a_frames = [1.0, 0.0];
+    /// let b_frames = [0.0, 1.0];
+    /// let a_signal = signal::from_iter(a_frames);
+    /// let b_signal = signal::from_iter(b_frames);
+    /// let mut signal = a_signal.mix(b_signal, 0.25);
+    /// assert_eq!(signal.next(), 0.75);
+    /// assert_eq!(signal.next(), 0.25);
+    /// ```
+    fn mix<O>(self, other: O, ratio: f32) -> Mix<Self, O>
+    where
+        Self: Sized,
+        O: Signal,
+    {
+        Mix {
+            signal: self,
+            other,
+            ratio: ratio.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl<T> SignalMix for T where T: Signal {}
+
+/// Mixes samples yielded by `signal` and `other` in the given ratio.
+#[derive(Clone)]
+pub struct Mix<S, O>
+where
+    S: Signal,
+    O: Signal,
+{
+    signal: S,
+    other: O,
+    ratio: f32,
+}
+
+impl<S, O> Signal for Mix<S, O>
+where
+    S: Signal,
+    O: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let a = self.signal.next();
+        let b = self.other.next();
+        a + (b - a) * self.ratio
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted() || self.other.is_exhausted()
+    }
+}
@@ -4,20 +4,64 @@
 //! this implementation supports `#[no_std]` on stable and is concerned only
 //! about mono f32 frames.
 
+mod add_amp;
+mod buffered;
 mod clip_amp;
 mod constant;
+mod crossfade;
+mod from_fn;
 mod from_iterator;
+mod inspect;
+mod inverse;
+mod map;
+mod mix;
 mod mul_amp;
+mod noise;
+mod offset_amp;
+mod oscillate;
+mod poly_blep;
+mod saw;
+mod scale_amp;
 mod signal_trait;
 mod sine;
+mod square;
 mod take;
+#[cfg(test)]
+pub(crate) mod test_utils;
+mod triangle;
+mod until_exhausted;
+mod waveform;
+mod zip_map;
 
+pub use add_amp::SignalAddAmp;
+pub use buffered::SignalBuffered;
 pub use clip_amp::SignalClipAmp;
 pub use constant::constant;
+pub use crossfade::SignalCrossfade;
+pub use from_fn::from_fn;
 pub use from_iterator::from_iter;
+pub use inspect::SignalInspect;
+pub use inverse::SignalInverse;
+pub use map::SignalMap;
+pub use mix::SignalMix;
 pub use mul_amp::SignalMulAmp;
+pub(crate) use noise::XorShift;
+pub use noise::{brown_noise, pink_noise, velvet_noise, white_noise};
+pub use offset_amp::SignalOffsetAmp;
+pub use oscillate::SignalOscillate;
+pub use saw::saw;
+pub use scale_amp::SignalScaleAmp;
 pub use signal_trait::Signal;
 pub use sine::sine;
+pub use square::square;
 pub use take::SignalTake;
+pub use triangle::triangle;
+pub use until_exhausted::SignalUntilExhausted;
+pub(crate) use waveform::wrap;
+pub use waveform::Waveform;
+pub use zip_map::SignalZipMap;
 
 pub const EQUILIBRIUM: f32 = 0.0;
+
+/// Size of stack buffers used by combinators to process blocks of samples.
+const BLOCK_SIZE: usize = 32;
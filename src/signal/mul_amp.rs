@@ -1,4 +1,4 @@
-use super::Signal;
+use super::{Signal, BLOCK_SIZE};
 
 /// Multiplies samples yielded by signal by given signal.
 pub trait SignalMulAmp: Signal {
@@ -51,4 +51,21 @@ where
     fn next(&mut self) -> f32 {
         self.signal.next() * self.other.next()
     }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted() || self.other.is_exhausted()
+    }
+
+    fn fill(&mut self, buffer: &mut [f32]) {
+        let mut other = [0.0; BLOCK_SIZE];
+        for chunk in buffer.chunks_mut(BLOCK_SIZE) {
+            let other = &mut other[..chunk.len()];
+            self.signal.fill(chunk);
+            self.other.fill(other);
+            for (x, o) in chunk.iter_mut().zip(other.iter()) {
+                *x *= *o;
+            }
+        }
+    }
 }
@@ -0,0 +1,338 @@
+//! Generate white, pink, brown and velvet noise signals.
+//!
+//! All the generators are driven by a seedable xorshift pseudo-random number
+//! generator, so they are deterministic for a given seed.
+
+use super::Signal;
+
+/// Produces white noise with uniform distribution between -1.0 and 1.0.
+///
+/// # Example
+///
+/// ```rust
+/// use sirena::signal::{self, Signal};
+/// let mut signal = signal::white_noise(42);
+/// for _ in 0..100 {
+///     let x = signal.next();
+///     assert!(x >= -1.0 && x < 1.0);
+/// }
+/// ```
+pub fn white_noise(seed: u32) -> WhiteNoise {
+    WhiteNoise {
+        rng: XorShift::new(seed),
+    }
+}
+
+/// A white noise generator.
+#[derive(Clone)]
+pub struct WhiteNoise {
+    rng: XorShift,
+}
+
+impl Signal for WhiteNoise {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.rng.next_bipolar()
+    }
+}
+
+const PINK_ROWS: usize = 16;
+
+/// Produces pink noise with power decreasing by 3 dB per octave.
+///
+/// Uses Voss-McCartney algorithm, summing white noise sources updated at
+/// octave-spaced rates. Output stays between -1.0 and 1.0.
+///
+/// # Example
+///
+/// ```rust
+/// use sirena::signal::{self, Signal};
+/// let mut signal = signal::pink_noise(42);
+/// for _ in 0..100 {
+///     let x = signal.next();
+///     assert!(x >= -1.0 && x < 1.0);
+/// }
+/// ```
+pub fn pink_noise(seed: u32) -> PinkNoise {
+    let mut rng = XorShift::new(seed);
+    let mut rows = [0.0; PINK_ROWS];
+    rows.iter_mut().for_each(|x| *x = rng.next_bipolar());
+    let sum = rows.iter().sum();
+    PinkNoise {
+        rng,
+        rows,
+        sum,
+        counter: 0,
+    }
+}
+
+/// A pink noise generator.
+#[derive(Clone)]
+pub struct PinkNoise {
+    rng: XorShift,
+    rows: [f32; PINK_ROWS],
+    sum: f32,
+    counter: u32,
+}
+
+impl Signal for PinkNoise {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        // Row with index `n` gets updated every 2^(n+1) samples.
+        self.counter = self.counter.wrapping_add(1);
+        let row = self.counter.trailing_zeros() as usize;
+        if row < PINK_ROWS {
+            let value = self.rng.next_bipolar();
+            self.sum += value - self.rows[row];
+            self.rows[row] = value;
+        }
+
+        (self.sum + self.rng.next_bipolar()) / (PINK_ROWS + 1) as f32
+    }
+}
+
+/// Produces brown noise with power decreasing by 6 dB per octave.
+///
+/// White noise is integrated using a leaky integrator, preventing it from
+/// drifting away. The leak flattens the spectrum below 5 Hz regardless of
+/// the sample rate `fs`.
+///
+/// # Example
+///
+/// ```rust
+/// use sirena::signal::{self, Signal};
+/// let mut signal = signal::brown_noise(48000.0, 42);
+/// for _ in 0..100 {
+///     let x = signal.next();
+///     assert!(x >= -1.0 && x < 1.0);
+/// }
+/// ```
+pub fn brown_noise(fs: f32, seed: u32) -> BrownNoise {
+    const CORNER: f32 = 5.0;
+    // Standard deviation of the output before clipping.
+    const DEVIATION: f32 = 0.2;
+
+    let leak = f32::min(2.0 * core::f32::consts::PI * CORNER / fs, 1.0);
+    // Integrated uniform noise has variance of about leak / 6.
+    let gain = DEVIATION / libm::sqrtf(leak / 6.0);
+    BrownNoise {
+        rng: XorShift::new(seed),
+        leak,
+        gain,
+        value: 0.0,
+    }
+}
+
+/// A brown noise generator.
+#[derive(Clone)]
+pub struct BrownNoise {
+    rng: XorShift,
+    leak: f32,
+    gain: f32,
+    value: f32,
+}
+
+impl Signal for BrownNoise {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.value = (self.value + self.leak * self.rng.next_bipolar()) / (1.0 + self.leak);
+        (self.value * self.gain).clamp(-1.0, 1.0)
+    }
+}
+
+/// Produces velvet noise, a sparse sequence of randomly placed impulses of
+/// random sign.
+///
+/// The `density` sets the number of impulses per second. Exactly one impulse
+/// is placed within each period of `fs / density` samples.
+///
+/// # Example
+///
+/// ```rust
+/// use sirena::signal::{self, Signal, SignalTake};
+/// // 100 impulses per second sampled 1000 times per second.
+/// let signal = signal::velvet_noise(1000.0, 100.0, 42);
+/// let impulses = signal.take(1000).filter(|x| *x != 0.0).count();
+/// assert_eq!(impulses, 100);
+/// ```
+pub fn velvet_noise(fs: f32, density: f32, seed: u32) -> VelvetNoise {
+    let period = u32::max((fs / density) as u32, 1);
+    let mut noise = VelvetNoise {
+        rng: XorShift::new(seed),
+        period,
+        position: 0,
+        impulse_position: 0,
+        impulse: 0.0,
+    };
+    noise.place_impulse();
+    noise
+}
+
+/// A velvet noise generator.
+#[derive(Clone)]
+pub struct VelvetNoise {
+    rng: XorShift,
+    period: u32,
+    position: u32,
+    impulse_position: u32,
+    impulse: f32,
+}
+
+impl VelvetNoise {
+    fn place_impulse(&mut self) {
+        let random = self.rng.next_u32();
+        self.impulse_position = (random >> 1) % self.period;
+        self.impulse = if random & 1 == 0 { 1.0 } else { -1.0 };
+    }
+}
+
+impl Signal for VelvetNoise {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let sample = if self.position == self.impulse_position {
+            self.impulse
+        } else {
+            0.0
+        };
+
+        self.position += 1;
+        if self.position == self.period {
+            self.position = 0;
+            self.place_impulse();
+        }
+
+        sample
+    }
+}
+
+/// Xorshift pseudo-random number generator.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone)]
+pub(crate) struct XorShift {
+    state: u32,
+}
+
+impl XorShift {
+    pub fn new(seed: u32) -> Self {
+        // Xorshift would stay stuck in zero state.
+        let state = if seed == 0 { 0x9E37_79B9 } else { seed };
+        Self { state }
+    }
+
+    #[inline]
+    fn next_u32(&mut self) -> u32 {
+        let mut x = self.state;
+        x ^= x << 13;
+        x ^= x >> 17;
+        x ^= x << 5;
+        self.state = x;
+        x
+    }
+
+    /// Yield random number between -1.0 and 1.0.
+    #[inline]
+    pub fn next_bipolar(&mut self) -> f32 {
+        const SCALE: f32 = 1.0 / (1 << 23) as f32;
+        (self.next_u32() >> 8) as f32 * SCALE - 1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spectral_analysis::SpectralAnalysis;
+
+    const SAMPLE_RATE: u32 = 1024;
+
+    // Measure power ratio between the octave starting at a 32nd of the
+    // sample rate and three octaves above it, averaged across multiple
+    // analyses to reduce variance.
+    fn octave_ratio<S: Signal>(signal: &mut S, sample_rate: u32) -> f32 {
+        const RUNS: usize = 32;
+        let fs = sample_rate as f32;
+        let mut low = 0.0;
+        let mut high = 0.0;
+        for _ in 0..RUNS {
+            let mut buffer = [0.0; 1024];
+            buffer.iter_mut().for_each(|x| *x = signal.next());
+            let analysis = SpectralAnalysis::analyze(&buffer, sample_rate);
+            low += analysis.mean_magnitude(fs / 32.0, fs / 16.0);
+            high += analysis.mean_magnitude(fs / 4.0, fs / 2.0);
+        }
+        low / high
+    }
+
+    #[test]
+    fn white_noise_has_flat_spectrum() {
+        let ratio = octave_ratio(&mut white_noise(1), SAMPLE_RATE);
+        assert_relative_eq!(ratio, 1.0, max_relative = 0.3);
+    }
+
+    #[test]
+    fn pink_noise_decreases_by_3_db_per_octave() {
+        let ratio = octave_ratio(&mut pink_noise(1), SAMPLE_RATE);
+        assert_relative_eq!(ratio, 8.0, max_relative = 0.5);
+    }
+
+    #[test]
+    fn brown_noise_decreases_by_6_db_per_octave() {
+        let ratio = octave_ratio(&mut brown_noise(SAMPLE_RATE as f32, 1), SAMPLE_RATE);
+        assert_relative_eq!(ratio, 64.0, max_relative = 0.5);
+    }
+
+    #[test]
+    fn brown_noise_keeps_slope_on_high_sample_rate() {
+        const SAMPLE_RATE: u32 = 48000;
+        const DECIMATION: usize = 32;
+        const RUNS: usize = 32;
+
+        // Averaging blocks of samples brings the low octaves into the
+        // resolution of the analysis.
+        let mut noise = brown_noise(SAMPLE_RATE as f32, 1);
+        let mut low = 0.0;
+        let mut high = 0.0;
+        for _ in 0..RUNS {
+            let mut buffer = [0.0; 1024];
+            for x in buffer.iter_mut() {
+                *x = (0..DECIMATION).map(|_| noise.next()).sum::<f32>() / DECIMATION as f32;
+            }
+            let analysis = SpectralAnalysis::analyze(&buffer, SAMPLE_RATE / DECIMATION as u32);
+            low += analysis.mean_magnitude(20.0, 40.0);
+            high += analysis.mean_magnitude(160.0, 320.0);
+        }
+
+        assert_relative_eq!(low / high, 64.0, max_relative = 0.5);
+    }
+
+    #[test]
+    fn brown_noise_keeps_level_across_sample_rates() {
+        let rms = |fs: f32| {
+            let mut signal = brown_noise(fs, 1);
+            let sum: f32 = (0..(fs as usize * 4)).map(|_| signal.next().powi(2)).sum();
+            libm::sqrtf(sum / (fs * 4.0))
+        };
+        assert_relative_eq!(rms(1024.0), rms(48000.0), max_relative = 0.5);
+    }
+
+    #[test]
+    fn velvet_noise_has_flat_spectrum() {
+        let ratio = octave_ratio(&mut velvet_noise(SAMPLE_RATE as f32, 200.0, 1), SAMPLE_RATE);
+        assert_relative_eq!(ratio, 1.0, max_relative = 0.3);
+    }
+
+    #[test]
+    fn same_seed_yields_same_noise() {
+        let mut a = white_noise(7);
+        let mut b = white_noise(7);
+        for _ in 0..100 {
+            assert_relative_eq!(a.next(), b.next());
+        }
+    }
+
+    #[test]
+    fn zero_seed_does_not_get_stuck() {
+        let mut signal = white_noise(0);
+        let first = signal.next();
+        assert!((0..100).any(|_| signal.next() != first));
+    }
+}
@@ -0,0 +1,59 @@
+use super::Signal;
+
+/// Offset samples yielded by signal.
+pub trait SignalOffsetAmp: Signal {
+    /// Offsets the amplitude of the signal by the given value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalOffsetAmp};
+    /// let frames = [0.5, -1.0];
+    /// let mut signal = signal::from_iter(frames).offset_amp(1.0);
+    /// assert_eq!(signal.next(), 1.5);
+    /// assert_eq!(signal.next(), 0.0);
+    /// ```
+    fn offset_amp(self, offset: f32) -> OffsetAmp<Self>
+    where
+        Self: Sized,
+    {
+        OffsetAmp {
+            signal: self,
+            offset,
+        }
+    }
+}
+
+impl<T> SignalOffsetAmp for T where T: Signal {}
+
+/// Offsets samples yielded by `signal` by the given value.
+#[derive(Clone)]
+pub struct OffsetAmp<S>
+where
+    S: Signal,
+{
+    signal: S,
+    offset: f32,
+}
+
+impl<S> Signal for OffsetAmp<S>
+where
+    S: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.signal.next() + self.offset
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted()
+    }
+
+    fn fill(&mut self, buffer: &mut [f32]) {
+        self.signal.fill(buffer);
+        for x in buffer.iter_mut() {
+            *x += self.offset;
+        }
+    }
+}
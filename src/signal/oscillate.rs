@@ -0,0 +1,154 @@
+//! Drive an oscillator by frequency and phase given by other signals.
+
+use super::constant::{constant, Constant};
+use super::waveform::{wrap, Waveform};
+use super::Signal;
+
+/// Use samples yielded by signal as frequency of an oscillator.
+pub trait SignalOscillate: Signal {
+    /// Produces a wave of given shape, oscillating at frequency in hz read
+    /// from the signal on each tick.
+    ///
+    /// This allows for linear frequency modulation. Negative frequency runs
+    /// the oscillator backwards, enabling through-zero frequency modulation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate approx;
+    /// # fn main() {
+    /// use sirena::signal::{self, Signal, SignalOscillate, Waveform};
+    /// let frequency = [1.0, 1.0, -1.0, -1.0];
+    /// let mut signal = signal::from_iter(frequency).oscillate(4.0, Waveform::Sine);
+    /// assert_relative_eq!(signal.next(), 0.0);
+    /// assert_relative_eq!(signal.next(), 1.0);
+    /// assert_relative_eq!(signal.next(), 0.0, epsilon = 0.0001);
+    /// assert_relative_eq!(signal.next(), 1.0);
+    /// # }
+    /// ```
+    fn oscillate(self, fs: f32, waveform: Waveform) -> Oscillate<Self, Constant>
+    where
+        Self: Sized,
+    {
+        Oscillate {
+            frequency: self,
+            phase_offset: constant(0.0),
+            waveform,
+            phase: 0.0,
+            fs,
+        }
+    }
+}
+
+impl<T> SignalOscillate for T where T: Signal {}
+
+/// An oscillator with frequency taken from `frequency` and phase offset from
+/// `phase_offset` signal.
+#[derive(Clone)]
+pub struct Oscillate<F, P>
+where
+    F: Signal,
+    P: Signal,
+{
+    frequency: F,
+    phase_offset: P,
+    waveform: Waveform,
+    phase: f32,
+    fs: f32,
+}
+
+impl<F, P> Oscillate<F, P>
+where
+    F: Signal,
+    P: Signal,
+{
+    /// Offset the phase of the oscillator by samples of the given signal.
+    ///
+    /// The offset is expressed in cycles, `1.0` being a full period. This
+    /// allows for phase modulation.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate approx;
+    /// # fn main() {
+    /// use sirena::signal::{self, Signal, SignalOscillate, Waveform};
+    /// let mut signal = signal::constant(1.0)
+    ///     .oscillate(4.0, Waveform::Sine)
+    ///     .phase_mod(signal::constant(0.25));
+    /// assert_relative_eq!(signal.next(), 1.0);
+    /// assert_relative_eq!(signal.next(), 0.0, epsilon = 0.0001);
+    /// # }
+    /// ```
+    pub fn phase_mod<O>(self, phase_offset: O) -> Oscillate<F, O>
+    where
+        O: Signal,
+    {
+        Oscillate {
+            frequency: self.frequency,
+            phase_offset,
+            waveform: self.waveform,
+            phase: self.phase,
+            fs: self.fs,
+        }
+    }
+
+    /// Change the shape of the wave, keeping the current phase.
+    pub fn set_waveform(&mut self, waveform: Waveform) -> &mut Self {
+        self.waveform = waveform;
+        self
+    }
+}
+
+impl<F, P> Signal for Oscillate<F, P>
+where
+    F: Signal,
+    P: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let step = self.frequency.next() / self.fs;
+        let phase = wrap(self.phase + self.phase_offset.next());
+        let sample = self.waveform.sample(phase, f32::abs(step));
+        self.phase = wrap(self.phase + step);
+        sample
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.frequency.is_exhausted() || self.phase_offset.is_exhausted()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::test_utils::harmonic_to_alias_ratio;
+    use crate::signal::{from_iter, saw, SignalTake};
+
+    #[test]
+    fn match_saw_generator_with_constant_frequency() {
+        let mut modulated = constant(50.0).oscillate(1024.0, Waveform::Saw);
+        let mut static_saw = saw(1024.0, 50.0);
+        for _ in 0..1000 {
+            assert_relative_eq!(modulated.next(), static_saw.next(), epsilon = 0.001);
+        }
+    }
+
+    #[test]
+    fn suppress_aliasing_with_negative_frequency() {
+        let mut signal = constant(-50.0).oscillate(1024.0, Waveform::Square);
+        assert!(harmonic_to_alias_ratio(&mut signal, 50.0) > 200.0);
+    }
+
+    #[test]
+    fn keep_phase_within_cycle_on_through_zero_modulation() {
+        let frequency = [-300.0, 300.0].into_iter().cycle();
+        let signal = from_iter(frequency).oscillate(1024.0, Waveform::Triangle);
+        for x in signal.take(1000) {
+            assert!((-1.0..=1.0).contains(&x));
+        }
+    }
+}
@@ -0,0 +1,38 @@
+//! Polynomial corrections used to band-limit naive waveforms.
+//!
+//! Both functions expect `phase` within `[0.0, 1.0)` where the discontinuity
+//! is located at `0.0` and `step` being the phase increment per sample.
+
+/// Residual of a band-limited step of height 2.0.
+///
+/// Add it to a naive waveform to smoothen its upward jumps, subtract it to
+/// smoothen its downward jumps.
+#[inline]
+pub fn poly_blep(phase: f32, step: f32) -> f32 {
+    if phase < step {
+        let t = phase / step;
+        t + t - t * t - 1.0
+    } else if phase > 1.0 - step {
+        let t = (phase - 1.0) / step;
+        t * t + t + t + 1.0
+    } else {
+        0.0
+    }
+}
+
+/// Residual of a band-limited ramp with slope change of 2.0 per sample.
+///
+/// Add it to a naive waveform to round its rising corners, subtract it to
+/// round its falling corners. It must be scaled by the actual slope change.
+#[inline]
+pub fn poly_blamp(phase: f32, step: f32) -> f32 {
+    if phase < step {
+        let t = 1.0 - phase / step;
+        t * t * t / 3.0
+    } else if phase > 1.0 - step {
+        let t = (phase - 1.0) / step + 1.0;
+        t * t * t / 3.0
+    } else {
+        0.0
+    }
+}
@@ -0,0 +1,64 @@
+//! Generate band-limited sawtooth wave signal.
+
+use super::waveform;
+use super::Signal;
+
+/// Produces a signal that yields a rising sawtooth wave oscillating at the
+/// given hz.
+///
+/// The wave is band-limited using PolyBLEP, keeping aliasing low up to the
+/// Nyquist frequency.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate approx;
+/// # fn main() {
+/// // Generates a sawtooth wave signal at 1 hz to be sampled 8 times per second.
+/// use sirena::signal::{self, Signal};
+/// let mut signal = signal::saw(8.0, 1.0);
+/// assert_relative_eq!(signal.next(), 0.0);
+/// assert_relative_eq!(signal.next(), -0.75);
+/// assert_relative_eq!(signal.next(), -0.5);
+/// # }
+/// ```
+pub fn saw(fs: f32, hz: f32) -> Saw {
+    Saw {
+        phase: 0.0,
+        step: hz / fs,
+    }
+}
+
+/// A band-limited sawtooth wave signal generator.
+#[derive(Clone)]
+pub struct Saw {
+    phase: f32,
+    step: f32,
+}
+
+impl Signal for Saw {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let sample = waveform::saw(self.phase, self.step);
+
+        self.phase += self.step;
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+        }
+
+        sample
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::test_utils::harmonic_to_alias_ratio;
+
+    #[test]
+    fn suppress_aliasing() {
+        let mut signal = saw(1024.0, 50.0);
+        assert!(harmonic_to_alias_ratio(&mut signal, 50.0) > 200.0);
+    }
+}
@@ -0,0 +1,56 @@
+use super::Signal;
+
+/// Scale samples yielded by signal.
+pub trait SignalScaleAmp: Signal {
+    /// Multiplies the amplitude of the signal by the given value.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalScaleAmp};
+    /// let frames = [0.5, -1.0];
+    /// let mut signal = signal::from_iter(frames).scale_amp(2.0);
+    /// assert_eq!(signal.next(), 1.0);
+    /// assert_eq!(signal.next(), -2.0);
+    /// ```
+    fn scale_amp(self, amp: f32) -> ScaleAmp<Self>
+    where
+        Self: Sized,
+    {
+        ScaleAmp { signal: self, amp }
+    }
+}
+
+impl<T> SignalScaleAmp for T where T: Signal {}
+
+/// Multiplies samples yielded by `signal` by the given value.
+#[derive(Clone)]
+pub struct ScaleAmp<S>
+where
+    S: Signal,
+{
+    signal: S,
+    amp: f32,
+}
+
+impl<S> Signal for ScaleAmp<S>
+where
+    S: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.signal.next() * self.amp
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted()
+    }
+
+    fn fill(&mut self, buffer: &mut [f32]) {
+        self.signal.fill(buffer);
+        for x in buffer.iter_mut() {
+            *x *= self.amp;
+        }
+    }
+}
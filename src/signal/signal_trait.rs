@@ -17,6 +17,57 @@ pub trait Signal {
     /// ```
     fn next(&mut self) -> f32;
 
+    /// Fill the given buffer with following samples of the signal.
+    ///
+    /// This is equivalent to calling `next` for each item of the buffer.
+    /// Implementors may override it with a tighter loop processing the whole
+    /// block at once.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate approx;
+    /// # fn main() {
+    /// use sirena::signal::{self, Signal};
+    /// let frames = [0.1, 0.2, 0.3];
+    /// let mut signal = signal::from_iter(frames);
+    /// let mut buffer = [0.0; 2];
+    /// signal.fill(&mut buffer);
+    /// assert_relative_eq!(buffer[0], 0.1);
+    /// assert_relative_eq!(buffer[1], 0.2);
+    /// assert_relative_eq!(signal.next(), 0.3);
+    /// # }
+    /// ```
+    fn fill(&mut self, buffer: &mut [f32]) {
+        for x in buffer.iter_mut() {
+            *x = self.next();
+        }
+    }
+
+    /// Whether the signal is exhausted of samples.
+    ///
+    /// Most signals are infinite and never get exhausted. Others, such as
+    /// those created through [`from_iter`](super::from_iter), yield
+    /// [`EQUILIBRIUM`](super::EQUILIBRIUM) once their source runs out and
+    /// report it through this method.
+    ///
+    /// Combinators are exhausted as soon as any of their inputs is.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal};
+    /// let frames = [0.1];
+    /// let mut signal = signal::from_iter(frames);
+    /// assert!(!signal.is_exhausted());
+    /// signal.next();
+    /// assert!(signal.is_exhausted());
+    /// ```
+    fn is_exhausted(&self) -> bool {
+        false
+    }
+
     /// Borrows a Signal rather than consuming it.
     ///
     /// This is useful to allow applying signal adaptors while still retaining ownership of the
@@ -47,7 +98,7 @@ pub trait Signal {
     }
 }
 
-impl<'a, S> Signal for &'a mut S
+impl<S> Signal for &mut S
 where
     S: Signal + ?Sized,
 {
@@ -55,4 +106,39 @@ where
     fn next(&mut self) -> f32 {
         (**self).next()
     }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        (**self).is_exhausted()
+    }
+
+    #[inline]
+    fn fill(&mut self, buffer: &mut [f32]) {
+        (**self).fill(buffer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::signal::test_utils::assert_fill_matches_next;
+    use crate::signal::{
+        constant, sine, Signal, SignalAddAmp, SignalClipAmp, SignalMulAmp, SignalOffsetAmp,
+        SignalScaleAmp,
+    };
+
+    // Chain all the signals overriding `fill`, each calling `fill` of its
+    // inputs.
+    fn chain() -> impl Signal {
+        sine(100.0, 3.0)
+            .add_amp(constant(0.25))
+            .mul_amp(sine(100.0, 7.0))
+            .scale_amp(2.0)
+            .offset_amp(-0.1)
+            .clip_amp(0.8)
+    }
+
+    #[test]
+    fn fill_matches_sample_by_sample_processing() {
+        assert_fill_matches_next(chain(), chain());
+    }
 }
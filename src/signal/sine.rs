@@ -1,5 +1,6 @@
 //! Generate sine wave signal.
 
+use super::waveform::{self, wrap};
 use super::Signal;
 
 /// Produces a signal that yields a sine wave oscillating at the given hz.
@@ -36,9 +37,56 @@ pub struct Sine {
 impl Signal for Sine {
     #[inline]
     fn next(&mut self) -> f32 {
-        const PI_2: f32 = core::f32::consts::PI * 2.0;
-        let sample = libm::sinf(PI_2 * self.phase);
-        self.phase += self.step;
+        let sample = waveform::sine(self.phase);
+        self.phase = wrap(self.phase + self.step);
         sample
     }
+
+    fn fill(&mut self, buffer: &mut [f32]) {
+        let mut phase = self.phase;
+        let step = self.step;
+        for x in buffer.iter_mut() {
+            *x = waveform::sine(phase);
+            phase = wrap(phase + step);
+        }
+        self.phase = phase;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::test_utils::assert_fill_matches_next;
+    use core::f32::consts::PI;
+
+    #[test]
+    fn fill_matches_sample_by_sample_processing() {
+        for hz in [3.0, -3.0, 130.0] {
+            assert_fill_matches_next(sine(100.0, hz), sine(100.0, hz));
+        }
+    }
+
+    #[test]
+    fn keep_precision_when_running_long() {
+        const FS: f32 = 48000.0;
+
+        for hz in [1000.0, -1000.0, 49000.0] {
+            let mut signal = sine(FS, hz);
+            for _ in 0..48000 * 60 {
+                signal.next();
+            }
+
+            // Samples of an evenly advancing sine satisfy the recurrence
+            // x[n + 1] = 2 cos(w) x[n] - x[n - 1].
+            let coefficient = 2.0 * libm::cosf(2.0 * PI * hz / FS);
+            let mut x0 = signal.next();
+            let mut x1 = signal.next();
+            for _ in 0..100 {
+                let x2 = signal.next();
+                assert_abs_diff_eq!(x2, coefficient * x1 - x0, epsilon = 0.001);
+                x0 = x1;
+                x1 = x2;
+            }
+        }
+    }
 }
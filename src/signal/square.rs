@@ -0,0 +1,86 @@
+//! Generate band-limited square and pulse wave signal.
+
+use super::waveform;
+use super::Signal;
+
+/// Produces a signal that yields a pulse wave oscillating at the given hz.
+///
+/// The `pulse_width` sets the portion of the cycle during which the signal
+/// stays high, `0.5` yields a square wave. It gets clamped between `0.0`
+/// and `1.0`.
+///
+/// The wave is band-limited using PolyBLEP, keeping aliasing low up to the
+/// Nyquist frequency.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate approx;
+/// # fn main() {
+/// // Generates a square wave signal at 1 hz to be sampled 8 times per second.
+/// use sirena::signal::{self, Signal};
+/// let mut signal = signal::square(8.0, 1.0, 0.5);
+/// assert_relative_eq!(signal.next(), 0.0);
+/// assert_relative_eq!(signal.next(), 1.0);
+/// assert_relative_eq!(signal.next(), 1.0);
+/// signal.next();
+/// assert_relative_eq!(signal.next(), 0.0);
+/// assert_relative_eq!(signal.next(), -1.0);
+/// # }
+/// ```
+pub fn square(fs: f32, hz: f32, pulse_width: f32) -> Square {
+    Square {
+        phase: 0.0,
+        step: hz / fs,
+        pulse_width: pulse_width.clamp(0.0, 1.0),
+    }
+}
+
+/// A band-limited pulse wave signal generator.
+#[derive(Clone)]
+pub struct Square {
+    phase: f32,
+    step: f32,
+    pulse_width: f32,
+}
+
+impl Square {
+    /// Set the portion of the cycle during which the signal stays high.
+    pub fn set_pulse_width(&mut self, pulse_width: f32) -> &mut Self {
+        self.pulse_width = pulse_width.clamp(0.0, 1.0);
+        self
+    }
+}
+
+impl Signal for Square {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let sample = waveform::pulse(self.phase, self.step, self.pulse_width);
+
+        self.phase += self.step;
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+        }
+
+        sample
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::test_utils::harmonic_to_alias_ratio;
+
+    #[test]
+    fn suppress_aliasing() {
+        let mut signal = square(1024.0, 50.0, 0.5);
+        assert!(harmonic_to_alias_ratio(&mut signal, 50.0) > 200.0);
+    }
+
+    #[test]
+    fn suppress_aliasing_with_narrow_pulse() {
+        let mut signal = square(1024.0, 50.0, 0.2);
+        assert!(harmonic_to_alias_ratio(&mut signal, 50.0) > 200.0);
+    }
+}
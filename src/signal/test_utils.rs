@@ -0,0 +1,44 @@
+//! Helpers shared by tests of signal generators.
+
+use super::Signal;
+use crate::spectral_analysis::SpectralAnalysis;
+
+const SAMPLE_RATE: u32 = 1024;
+
+/// Measure how much stronger are harmonics of the signal compared to its
+/// aliased components.
+///
+/// The signal is expected to be generated with sample rate of 1024 and
+/// integer `frequency`, so its harmonics fall precisely into analyzed bins.
+pub fn harmonic_to_alias_ratio<S: Signal>(signal: &mut S, frequency: f32) -> f32 {
+    let mut buffer = [0.0; SAMPLE_RATE as usize];
+    buffer.iter_mut().for_each(|x| *x = signal.next());
+
+    let analysis = SpectralAnalysis::analyze(&buffer, SAMPLE_RATE);
+
+    let mut harmonic = 0.0;
+    let mut alias = 0.0;
+    for bin in 1..(SAMPLE_RATE / 2) {
+        let magnitude = analysis.magnitude(bin as f32);
+        if bin % frequency as u32 == 0 {
+            harmonic += magnitude;
+        } else {
+            alias += magnitude;
+        }
+    }
+
+    harmonic / alias
+}
+
+/// Check that filling a buffer through `filled` yields the same samples as
+/// calling `next` on an identical `stepped` signal.
+///
+/// The buffer spans multiple blocks, so chunked processing is covered too.
+pub fn assert_fill_matches_next<S: Signal>(mut filled: S, mut stepped: S) {
+    let mut buffer = [0.0; 100];
+    filled.fill(&mut buffer);
+
+    for x in buffer.iter() {
+        assert_relative_eq!(*x, stepped.next());
+    }
+}
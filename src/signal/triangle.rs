@@ -0,0 +1,63 @@
+//! Generate band-limited triangle wave signal.
+
+use super::waveform;
+use super::Signal;
+
+/// Produces a signal that yields a triangle wave oscillating at the given hz.
+///
+/// The wave starts at zero and rises first, matching the phase of
+/// [`sine`](super::sine). It is band-limited using PolyBLAMP, keeping
+/// aliasing low up to the Nyquist frequency.
+///
+/// # Example
+///
+/// ```rust
+/// # #[macro_use]
+/// # extern crate approx;
+/// # fn main() {
+/// // Generates a triangle wave signal at 1 hz to be sampled 8 times per second.
+/// use sirena::signal::{self, Signal};
+/// let mut signal = signal::triangle(8.0, 1.0);
+/// assert_relative_eq!(signal.next(), 0.0);
+/// assert_relative_eq!(signal.next(), 0.5);
+/// # }
+/// ```
+pub fn triangle(fs: f32, hz: f32) -> Triangle {
+    Triangle {
+        phase: 0.0,
+        step: hz / fs,
+    }
+}
+
+/// A band-limited triangle wave signal generator.
+#[derive(Clone)]
+pub struct Triangle {
+    phase: f32,
+    step: f32,
+}
+
+impl Signal for Triangle {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let sample = waveform::triangle(self.phase, self.step);
+
+        self.phase += self.step;
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+        }
+
+        sample
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::test_utils::harmonic_to_alias_ratio;
+
+    #[test]
+    fn suppress_aliasing() {
+        let mut signal = triangle(1024.0, 50.0);
+        assert!(harmonic_to_alias_ratio(&mut signal, 50.0) > 20000.0);
+    }
+}
@@ -0,0 +1,53 @@
+use super::Signal;
+
+/// Iterate signal until it gets exhausted.
+pub trait SignalUntilExhausted: Signal {
+    /// Converts the `Signal` into an `Iterator` that will yield frames until
+    /// the signal gets exhausted.
+    ///
+    /// Note that infinite signals never get exhausted.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, SignalScaleAmp, SignalUntilExhausted};
+    /// let frames = [0.1, 0.2];
+    /// let mut signal = signal::from_iter(frames).scale_amp(2.0).until_exhausted();
+    /// assert_eq!(signal.next(), Some(0.2));
+    /// assert_eq!(signal.next(), Some(0.4));
+    /// assert_eq!(signal.next(), None);
+    /// ```
+    fn until_exhausted(self) -> UntilExhausted<Self>
+    where
+        Self: Sized,
+    {
+        UntilExhausted { signal: self }
+    }
+}
+
+impl<T> SignalUntilExhausted for T where T: Signal {}
+
+/// An iterator that yields frames from the inner `signal` until it gets
+/// exhausted.
+#[derive(Clone)]
+pub struct UntilExhausted<S>
+where
+    S: Signal,
+{
+    signal: S,
+}
+
+impl<S> Iterator for UntilExhausted<S>
+where
+    S: Signal,
+{
+    type Item = f32;
+
+    #[inline]
+    fn next(&mut self) -> Option<f32> {
+        if self.signal.is_exhausted() {
+            return None;
+        }
+        Some(self.signal.next())
+    }
+}
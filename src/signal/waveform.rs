@@ -0,0 +1,77 @@
+//! Shapes of waves produced by oscillators.
+
+use super::poly_blep::{poly_blamp, poly_blep};
+
+/// Waveform to be produced by an oscillator.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Sine,
+    Saw,
+    Square,
+    /// Pulse wave with given width, the portion of the cycle during which
+    /// it stays high.
+    Pulse(f32),
+    Triangle,
+}
+
+impl Waveform {
+    /// Sample the waveform at `phase` within `[0.0, 1.0)`.
+    ///
+    /// The `step` is the absolute phase increment per sample, used to
+    /// band-limit waveforms with discontinuities.
+    #[inline]
+    pub fn sample(&self, phase: f32, step: f32) -> f32 {
+        match self {
+            Waveform::Sine => sine(phase),
+            Waveform::Saw => saw(phase, step),
+            Waveform::Square => pulse(phase, step, 0.5),
+            Waveform::Pulse(width) => pulse(phase, step, width.clamp(0.0, 1.0)),
+            Waveform::Triangle => triangle(phase, step),
+        }
+    }
+}
+
+#[inline]
+pub fn sine(phase: f32) -> f32 {
+    const PI_2: f32 = core::f32::consts::PI * 2.0;
+    libm::sinf(PI_2 * phase)
+}
+
+#[inline]
+pub fn saw(phase: f32, step: f32) -> f32 {
+    let naive = 2.0 * phase - 1.0;
+    naive - poly_blep(phase, step)
+}
+
+#[inline]
+pub fn pulse(phase: f32, step: f32, width: f32) -> f32 {
+    let naive = if phase < width { 1.0 } else { -1.0 };
+    let falling_phase = wrap(phase - width);
+    naive + poly_blep(phase, step) - poly_blep(falling_phase, step)
+}
+
+#[inline]
+pub fn triangle(phase: f32, step: f32) -> f32 {
+    let shifted_phase = wrap(phase + 0.75);
+    let naive = 4.0 * f32::abs(shifted_phase - 0.5) - 1.0;
+
+    // Slope changes by 8 per cycle on each corner, the residual is
+    // defined for change of 2.
+    let slope = 4.0 * step;
+    let rising_corner = poly_blamp(wrap(phase + 0.25), step);
+    let falling_corner = poly_blamp(shifted_phase, step);
+    naive + slope * (rising_corner - falling_corner)
+}
+
+/// Wrap phase into `[0.0, 1.0)`.
+#[inline]
+pub fn wrap(phase: f32) -> f32 {
+    let wrapped = phase - libm::floorf(phase);
+    // Tiny negative phase may round up to exactly 1.0.
+    if wrapped >= 1.0 {
+        0.0
+    } else {
+        wrapped
+    }
+}
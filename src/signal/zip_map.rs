@@ -0,0 +1,65 @@
+use super::Signal;
+
+/// Combine samples yielded by two signals through a closure.
+pub trait SignalZipMap: Signal {
+    /// Combines samples of the signal and another signal through the given
+    /// closure.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal, SignalZipMap};
+    /// let a_frames = [0.5, -1.0];
+    /// let b_frames = [1.0, 0.5];
+    /// let a_signal = signal::from_iter(a_frames);
+    /// let b_signal = signal::from_iter(b_frames);
+    /// let mut signal = a_signal.zip_map(b_signal, f32::max);
+    /// assert_eq!(signal.next(), 1.0);
+    /// assert_eq!(signal.next(), 0.5);
+    /// ```
+    fn zip_map<O, M>(self, other: O, map: M) -> ZipMap<Self, O, M>
+    where
+        Self: Sized,
+        O: Signal,
+        M: FnMut(f32, f32) -> f32,
+    {
+        ZipMap {
+            signal: self,
+            other,
+            map,
+        }
+    }
+}
+
+impl<T> SignalZipMap for T where T: Signal {}
+
+/// Combines samples yielded by `signal` and `other` through the `map`
+/// closure.
+#[derive(Clone)]
+pub struct ZipMap<S, O, M>
+where
+    S: Signal,
+    O: Signal,
+    M: FnMut(f32, f32) -> f32,
+{
+    signal: S,
+    other: O,
+    map: M,
+}
+
+impl<S, O, M> Signal for ZipMap<S, O, M>
+where
+    S: Signal,
+    O: Signal,
+    M: FnMut(f32, f32) -> f32,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        (self.map)(self.signal.next(), self.other.next())
+    }
+
+    #[inline]
+    fn is_exhausted(&self) -> bool {
+        self.signal.is_exhausted() || self.other.is_exhausted()
+    }
+}
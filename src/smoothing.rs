@@ -0,0 +1,303 @@
+//! Smoothers preventing zipper noise on control changes.
+//!
+//! Parameters read at control rate, e.g. from a knob, change in steps.
+//! Smoothers turn these steps into continuous transitions. All of them
+//! implement `Signal`, so they can be used directly with combinators such as
+//! [`mul_amp`](crate::signal::SignalMulAmp).
+//!
+//! # Example
+//!
+//! ```rust
+//! use sirena::signal::{self, Signal, SignalMulAmp};
+//! use sirena::smoothing::OnePole;
+//!
+//! let mut volume = OnePole::new(48000);
+//! volume.set_time(0.01).set_target(0.5);
+//!
+//! let mut voice = signal::sine(48000.0, 440.0).mul_amp(&mut volume);
+//! voice.next();
+//! ```
+
+use crate::signal::Signal;
+
+/// Exponentially approaches the target value.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone)]
+pub struct OnePole {
+    sample_rate: u32,
+    coefficient: f32,
+    value: f32,
+    target: f32,
+}
+
+impl OnePole {
+    /// Initialize the smoother with zero time, following the target
+    /// immediately.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            coefficient: 1.0,
+            value: 0.0,
+            target: 0.0,
+        }
+    }
+
+    /// Set time constant in seconds, the time it takes to cover 63 % of the
+    /// distance to the target.
+    pub fn set_time(&mut self, time: f32) -> &mut Self {
+        let samples = time * self.sample_rate as f32;
+        self.coefficient = if samples > 0.0 {
+            1.0 - libm::expf(-1.0 / samples)
+        } else {
+            1.0
+        };
+        self
+    }
+
+    pub fn set_target(&mut self, target: f32) -> &mut Self {
+        self.target = target;
+        self
+    }
+
+    /// Jump to the given value immediately.
+    pub fn reset(&mut self, value: f32) -> &mut Self {
+        self.value = value;
+        self.target = value;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        self.value += (self.target - self.value) * self.coefficient;
+        self.value
+    }
+}
+
+impl Signal for OnePole {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.tick()
+    }
+}
+
+/// Approaches the target value linearly within a set time.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone)]
+pub struct LinearRamp {
+    sample_rate: u32,
+    samples: u32,
+    remaining: u32,
+    step: f32,
+    value: f32,
+    target: f32,
+}
+
+impl LinearRamp {
+    /// Initialize the smoother with zero time, following the target
+    /// immediately.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            samples: 0,
+            remaining: 0,
+            step: 0.0,
+            value: 0.0,
+            target: 0.0,
+        }
+    }
+
+    /// Set time in seconds it takes to reach a new target.
+    pub fn set_time(&mut self, time: f32) -> &mut Self {
+        self.samples = (time * self.sample_rate as f32) as u32;
+        self
+    }
+
+    pub fn set_target(&mut self, target: f32) -> &mut Self {
+        self.target = target;
+        if self.samples == 0 {
+            self.value = target;
+            self.remaining = 0;
+        } else {
+            self.step = (target - self.value) / self.samples as f32;
+            self.remaining = self.samples;
+        }
+        self
+    }
+
+    /// Jump to the given value immediately.
+    pub fn reset(&mut self, value: f32) -> &mut Self {
+        self.value = value;
+        self.target = value;
+        self.remaining = 0;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        if self.remaining > 0 {
+            self.remaining -= 1;
+            self.value = if self.remaining == 0 {
+                self.target
+            } else {
+                self.value + self.step
+            };
+        }
+        self.value
+    }
+}
+
+impl Signal for LinearRamp {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.tick()
+    }
+}
+
+/// Approaches the target value with limited rate of change.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone)]
+pub struct SlewLimiter {
+    sample_rate: u32,
+    rise: f32,
+    fall: f32,
+    value: f32,
+    target: f32,
+}
+
+impl SlewLimiter {
+    /// Initialize the smoother with unlimited rate, following the target
+    /// immediately.
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate,
+            rise: f32::INFINITY,
+            fall: f32::INFINITY,
+            value: 0.0,
+            target: 0.0,
+        }
+    }
+
+    /// Set both rising and falling rate in units per second.
+    pub fn set_rate(&mut self, rate: f32) -> &mut Self {
+        self.set_rise(rate).set_fall(rate)
+    }
+
+    /// Set rising rate in units per second.
+    pub fn set_rise(&mut self, rate: f32) -> &mut Self {
+        self.rise = rate / self.sample_rate as f32;
+        self
+    }
+
+    /// Set falling rate in units per second.
+    pub fn set_fall(&mut self, rate: f32) -> &mut Self {
+        self.fall = rate / self.sample_rate as f32;
+        self
+    }
+
+    pub fn set_target(&mut self, target: f32) -> &mut Self {
+        self.target = target;
+        self
+    }
+
+    /// Jump to the given value immediately.
+    pub fn reset(&mut self, value: f32) -> &mut Self {
+        self.value = value;
+        self.target = value;
+        self
+    }
+
+    pub fn value(&self) -> f32 {
+        self.value
+    }
+
+    pub fn tick(&mut self) -> f32 {
+        let difference = self.target - self.value;
+        self.value += difference.clamp(-self.fall, self.rise);
+        self.value
+    }
+}
+
+impl Signal for SlewLimiter {
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.tick()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: u32 = 1000;
+
+    #[test]
+    fn one_pole_follows_target_immediately_without_time() {
+        let mut smoother = OnePole::new(SAMPLE_RATE);
+        smoother.set_target(1.0);
+        assert_relative_eq!(smoother.tick(), 1.0);
+    }
+
+    #[test]
+    fn one_pole_covers_most_of_the_distance_in_time_constant() {
+        let mut smoother = OnePole::new(SAMPLE_RATE);
+        smoother.set_time(0.1).set_target(1.0);
+        let value = (0..100).fold(0.0, |_, _| smoother.tick());
+        assert_relative_eq!(value, 0.63, epsilon = 0.01);
+    }
+
+    #[test]
+    fn linear_ramp_reaches_target_in_time() {
+        let mut smoother = LinearRamp::new(SAMPLE_RATE);
+        smoother.set_time(0.1).set_target(1.0);
+        assert_relative_eq!(smoother.tick(), 0.01);
+        let value = (0..49).fold(0.0, |_, _| smoother.tick());
+        assert_relative_eq!(value, 0.5, epsilon = 0.0001);
+        let value = (0..50).fold(0.0, |_, _| smoother.tick());
+        assert_relative_eq!(value, 1.0);
+        assert_relative_eq!(smoother.tick(), 1.0);
+    }
+
+    #[test]
+    fn linear_ramp_restarts_from_current_value() {
+        let mut smoother = LinearRamp::new(SAMPLE_RATE);
+        smoother.set_time(0.1).set_target(1.0);
+        (0..50).for_each(|_| {
+            smoother.tick();
+        });
+        smoother.set_target(0.0);
+        let value = (0..50).fold(0.0, |_, _| smoother.tick());
+        assert_relative_eq!(value, 0.25, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn slew_limiter_limits_rate_of_change() {
+        let mut smoother = SlewLimiter::new(SAMPLE_RATE);
+        smoother.set_rise(10.0).set_fall(1.0).set_target(1.0);
+        assert_relative_eq!(smoother.tick(), 0.01);
+        let value = (0..99).fold(0.0, |_, _| smoother.tick());
+        assert_relative_eq!(value, 1.0, epsilon = 0.0001);
+
+        smoother.set_target(0.0);
+        assert_relative_eq!(smoother.tick(), 0.999, epsilon = 0.0001);
+    }
+
+    #[test]
+    fn slew_limiter_follows_target_immediately_without_rate() {
+        let mut smoother = SlewLimiter::new(SAMPLE_RATE);
+        smoother.set_target(-3.0);
+        assert_relative_eq!(smoother.tick(), -3.0);
+    }
+
+    #[test]
+    fn reset_jumps_to_value() {
+        let mut smoother = OnePole::new(SAMPLE_RATE);
+        smoother.set_time(1.0).reset(2.0);
+        assert_relative_eq!(smoother.tick(), 2.0);
+    }
+}
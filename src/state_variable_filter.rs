@@ -5,14 +5,23 @@ use micromath::F32Ext;
 
 use core::f32::consts::PI;
 
+use crate::saturation;
+use crate::signal::Signal;
+use crate::smoothing::OnePole;
+
 /// Yields filtered signal.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
 #[derive(Debug)]
 pub struct StateVariableFilter {
     sample_rate: u32,
+    frequency_scale: f32,
     bandform: Bandform,
-    f: f32,
-    q: f32,
+    limit: Limit,
+    f: OnePole,
+    q: OnePole,
+    smoothing: bool,
+    modulated_q_factor: f32,
+    modulated_q: f32,
     delay_1: f32,
     delay_2: f32,
 }
@@ -21,9 +30,14 @@ impl StateVariableFilter {
     pub fn new(sample_rate: u32) -> Self {
         let mut filter = Self {
             sample_rate,
+            frequency_scale: PI / sample_rate as f32,
             bandform: BandPass,
-            f: 0.0,
-            q: 0.0,
+            limit: HardClip(1.0),
+            f: OnePole::new(sample_rate),
+            q: OnePole::new(sample_rate),
+            smoothing: false,
+            modulated_q_factor: 0.0,
+            modulated_q: 2.0,
             delay_1: 0.0,
             delay_2: 0.0,
         };
@@ -37,13 +51,52 @@ impl StateVariableFilter {
         self
     }
 
+    /// Select how is the output of the filter limited.
+    ///
+    /// Output is hard clipped at 1.0 by default. Levels of `HardClip` and
+    /// `Saturate` are taken as absolute values, at least `f32::EPSILON`.
+    pub fn set_limit(&mut self, limit: Limit) -> &mut Self {
+        let valid_level = |level: f32| f32::max(level.abs(), f32::EPSILON);
+        self.limit = match limit {
+            Unlimited => Unlimited,
+            HardClip(level) => HardClip(valid_level(level)),
+            Saturate(level) => Saturate(valid_level(level)),
+        };
+        self
+    }
+
     pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
-        self.f = 2.0 * f32::sin((PI * frequency) / self.sample_rate as f32);
+        let f = 2.0 * f32::sin((PI * frequency) / self.sample_rate as f32);
+        // Without smoothing, jump right to the new value, so the smoother
+        // does not ramp from a stale one once smoothing gets enabled.
+        if self.smoothing {
+            self.f.set_target(f);
+        } else {
+            self.f.reset(f);
+        }
         self
     }
 
     pub fn set_q_factor(&mut self, q_factor: f32) -> &mut Self {
-        self.q = 1.0 / f32::max(q_factor, 0.5);
+        let q = 1.0 / f32::max(q_factor, 0.5);
+        if self.smoothing {
+            self.q.set_target(q);
+        } else {
+            self.q.reset(q);
+        }
+        self
+    }
+
+    /// Smoothen changes of frequency and Q factor over the given time
+    /// constant in seconds, preventing zipper noise when they are set at
+    /// control rate.
+    ///
+    /// Smoothing is disabled by default, so new values apply immediately.
+    /// Values set before smoothing gets enabled are not ramped to.
+    pub fn set_smoothing(&mut self, time: f32) -> &mut Self {
+        self.smoothing = time > 0.0;
+        self.f.set_time(time);
+        self.q.set_time(time);
         self
     }
 
@@ -59,6 +112,58 @@ impl StateVariableFilter {
         }
     }
 
+    /// Filter the signal, reading frequency and Q factor for each sample
+    /// from given signals.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, SignalOffsetAmp, SignalScaleAmp};
+    /// use sirena::state_variable_filter::{LowPass, StateVariableFilter};
+    ///
+    /// let mut filter = StateVariableFilter::new(48000);
+    /// filter.set_bandform(LowPass);
+    ///
+    /// let frequency = signal::sine(48000.0, 2.0).scale_amp(500.0).offset_amp(1000.0);
+    /// let q_factor = signal::constant(0.7);
+    ///
+    /// let mut buffer = [0.0; 32];
+    /// filter.process_modulated(&mut buffer, frequency, q_factor);
+    /// ```
+    pub fn process_modulated<F, Q>(&mut self, signal: &mut [f32], mut frequency: F, mut q_factor: Q)
+    where
+        F: Signal,
+        Q: Signal,
+    {
+        for x in signal.iter_mut() {
+            *x = self.tick_modulated(*x, frequency.next(), q_factor.next());
+        }
+    }
+
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let f = self.f.tick();
+        let q = self.q.tick();
+        self.filter(value, f, q)
+    }
+
+    /// Filter a single sample using frequency and Q factor given for this
+    /// tick only.
+    ///
+    /// Unlike `set_frequency`, this calculates the coefficient using a fast
+    /// polynomial approximation, so it is cheap enough to be called on every
+    /// sample. Smoothing does not apply here.
+    pub fn tick_modulated(&mut self, value: f32, frequency: f32, q_factor: f32) -> f32 {
+        let f = 2.0 * fast_sin(frequency * self.frequency_scale);
+
+        // Avoid the costly division while Q factor stays static.
+        if q_factor != self.modulated_q_factor {
+            self.modulated_q_factor = q_factor;
+            self.modulated_q = 1.0 / f32::max(q_factor, 0.5);
+        }
+
+        self.filter(value, f, self.modulated_q)
+    }
+
     // https://www.earlevel.com/main/2003/03/02/the-digital-state-variable-filter/
     //
     //             +----------------------------------------------------------+
@@ -74,10 +179,8 @@ impl StateVariableFilter {
     //      |                                                                      |
     //      +----------------------------------------------------------------------+
     //
-    pub fn tick(&mut self, value: f32) -> f32 {
-        let sum_3 = self.delay_1 * self.f + self.delay_2;
-        let sum_1 = value - sum_3 - self.delay_1 * self.q;
-        let sum_2 = sum_1 * self.f + self.delay_1;
+    fn filter(&mut self, value: f32, f: f32, q: f32) -> f32 {
+        let (sum_1, sum_2, sum_3) = self.integrate(value, f, q);
 
         let value = match self.bandform {
             LowPass => sum_3,
@@ -90,15 +193,85 @@ impl StateVariableFilter {
             }
         };
 
+        self.limit_output(value)
+    }
+
+    /// Filter a single sample, returning all the outputs at once.
+    ///
+    /// This is cheaper than running multiple filters, one for each bandform.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::state_variable_filter::StateVariableFilter;
+    ///
+    /// let mut filter = StateVariableFilter::new(48000);
+    /// filter.set_frequency(1000.0);
+    ///
+    /// let outputs = filter.tick_all(1.0);
+    /// assert!(outputs.low_pass < outputs.high_pass);
+    /// ```
+    pub fn tick_all(&mut self, value: f32) -> Outputs {
+        let f = self.f.tick();
+        let q = self.q.tick();
+
+        let (sum_1, sum_2, sum_3) = self.integrate(value, f, q);
+
+        Outputs {
+            low_pass: self.limit_output(sum_3),
+            high_pass: self.limit_output(sum_1),
+            band_pass: self.limit_output(sum_2),
+            band_reject: self.limit_output(sum_1 + sum_3),
+            peak: self.limit_output(sum_3 - sum_1),
+            all_pass: self.limit_output(sum_1 + sum_3 - q * sum_2),
+        }
+    }
+
+    fn integrate(&mut self, value: f32, f: f32, q: f32) -> (f32, f32, f32) {
+        let sum_3 = self.delay_1 * f + self.delay_2;
+        let sum_1 = value - sum_3 - self.delay_1 * q;
+        let mut sum_2 = sum_1 * f + self.delay_1;
+
+        if let Saturate(level) = self.limit {
+            sum_2 = level * saturation::tanh(sum_2 / level);
+        }
+
         self.delay_1 = sum_2;
         self.delay_2 = sum_3;
 
-        f32::max(f32::min(value, 1.0), -1.0)
+        (sum_1, sum_2, sum_3)
+    }
+
+    fn limit_output(&self, value: f32) -> f32 {
+        match self.limit {
+            HardClip(level) => {
+                // Unlike clamp, this turns NaN of an unstable filter into the level.
+                #[allow(clippy::manual_clamp)]
+                f32::max(f32::min(value, level), -level)
+            }
+            Unlimited | Saturate(_) => value,
+        }
     }
 }
 
+/// All outputs of the filter calculated from the same state.
 #[cfg_attr(feature = "defmt", derive(defmt::Format))]
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
+pub struct Outputs {
+    pub low_pass: f32,
+    pub high_pass: f32,
+    pub band_pass: f32,
+    pub band_reject: f32,
+    /// Difference of low and high pass, boosting frequencies around the
+    /// cutoff when resonant.
+    pub peak: f32,
+    /// Passes all frequencies with unchanged magnitude, shifting their
+    /// phase around the cutoff.
+    pub all_pass: f32,
+}
+
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Bandform {
     LowPass,
     HighPass,
@@ -108,10 +281,39 @@ pub enum Bandform {
 
 pub use Bandform::*;
 
+/// Limiting applied on the output of the filter.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Limit {
+    /// Output is passed through unchanged. Note that the filter gets
+    /// unstable when its frequency approaches a sixth of the sample rate.
+    Unlimited,
+    /// Output is hard clipped at the given positive level.
+    HardClip(f32),
+    /// The band pass integrator is softly saturated at the given positive
+    /// level inside the feedback loop, taming resonance the way analog
+    /// filters do.
+    Saturate(f32),
+}
+
+pub use Limit::*;
+
+/// Approximate sine on range between 0 and PI / 2 using Taylor series.
+///
+/// Input outside of the range is clamped. That is sufficient for the filter,
+/// which gets unstable long before reaching the upper limit.
+#[inline]
+fn fast_sin(x: f32) -> f32 {
+    let x = x.clamp(0.0, PI / 2.0);
+    let x2 = x * x;
+    x * (1.0 - x2 * (1.0 / 6.0) * (1.0 - x2 * (1.0 / 20.0) * (1.0 - x2 * (1.0 / 42.0))))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::spectral_analysis::SpectralAnalysis;
+    use crate::test_utils::noise;
     use rand::Rng;
 
     #[test]
@@ -236,4 +438,252 @@ mod tests {
 
         assert_relative_eq!(original_mean_magnitude, updated_mean_magnitude);
     }
+
+    #[test]
+    fn all_outputs_match_selected_bandforms() {
+        let mut filters = [LowPass, HighPass, BandPass, BandReject].map(|bandform| {
+            let mut filter = StateVariableFilter::new(1024);
+            filter.set_bandform(bandform).set_frequency(100.0);
+            filter
+        });
+        let mut multi_filter = StateVariableFilter::new(1024);
+        multi_filter.set_frequency(100.0);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..1024 {
+            let x = rng.gen_range(-1.0..=1.0);
+            let outputs = multi_filter.tick_all(x);
+            assert_relative_eq!(outputs.low_pass, filters[0].tick(x));
+            assert_relative_eq!(outputs.high_pass, filters[1].tick(x));
+            assert_relative_eq!(outputs.band_pass, filters[2].tick(x));
+            assert_relative_eq!(outputs.band_reject, filters[3].tick(x));
+        }
+    }
+
+    #[test]
+    fn all_pass_keeps_magnitude() {
+        const SAMPLE_RATE: u32 = 1024;
+
+        let mut signal = [0.0; 1024];
+        signal[0] = 1.0;
+
+        let mut filter = StateVariableFilter::new(SAMPLE_RATE);
+        filter.set_frequency(100.0);
+        signal
+            .iter_mut()
+            .for_each(|x| *x = filter.tick_all(*x).all_pass);
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        for frequency in [10.0, 50.0, 100.0, 150.0] {
+            assert_relative_eq!(analysis.magnitude(frequency), 1.0, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn peak_boosts_cutoff() {
+        const SAMPLE_RATE: u32 = 1024;
+
+        let mut signal = [0.0; 1024];
+        signal[0] = 1.0;
+
+        let mut filter = StateVariableFilter::new(SAMPLE_RATE);
+        filter.set_frequency(100.0).set_q_factor(5.0);
+        signal
+            .iter_mut()
+            .for_each(|x| *x = filter.tick_all(*x).peak);
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        assert!(analysis.magnitude(100.0) > analysis.magnitude(10.0) * 3.0);
+        assert!(analysis.magnitude(100.0) > analysis.magnitude(150.0) * 3.0);
+    }
+
+    fn resonate(filter: &mut StateVariableFilter, amplitude: f32) -> f32 {
+        let mut signal = [0.0; 1024];
+        for (i, x) in signal.iter_mut().enumerate() {
+            *x = amplitude * f32::sin(2.0 * PI * 100.0 * i as f32 / 1024.0);
+        }
+        filter.process(&mut signal);
+        signal
+            .iter()
+            .fold(0.0, |max, x| f32::max(max, f32::abs(*x)))
+    }
+
+    #[test]
+    fn hard_clip_output_by_default() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter
+            .set_bandform(BandPass)
+            .set_frequency(100.0)
+            .set_q_factor(10.0);
+        assert_relative_eq!(resonate(&mut filter, 1.0), 1.0);
+    }
+
+    #[test]
+    fn hard_clip_output_at_given_level() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter
+            .set_bandform(BandPass)
+            .set_frequency(100.0)
+            .set_q_factor(10.0)
+            .set_limit(HardClip(5.0));
+        assert_relative_eq!(resonate(&mut filter, 1.0), 5.0);
+    }
+
+    #[test]
+    fn hard_clip_at_absolute_value_of_negative_level() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter
+            .set_bandform(BandPass)
+            .set_frequency(100.0)
+            .set_q_factor(10.0)
+            .set_limit(HardClip(-5.0));
+        assert_relative_eq!(resonate(&mut filter, 1.0), 5.0);
+    }
+
+    #[test]
+    fn pass_output_unlimited() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter
+            .set_bandform(HighPass)
+            .set_frequency(10.0)
+            .set_limit(Unlimited);
+        assert!(resonate(&mut filter, 5.0) > 4.5);
+    }
+
+    #[test]
+    fn saturate_resonance() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter
+            .set_bandform(BandPass)
+            .set_frequency(100.0)
+            .set_q_factor(10.0)
+            .set_limit(Saturate(1.0));
+        let peak = resonate(&mut filter, 1.0);
+        assert!(peak <= 1.0);
+        assert!(peak > 0.9);
+    }
+
+    #[test]
+    fn keep_unstable_filter_bounded_with_saturation() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter
+            .set_bandform(LowPass)
+            .set_frequency(300.0)
+            .set_q_factor(10.0)
+            .set_limit(Saturate(1.0));
+        let peak = resonate(&mut filter, 1.0);
+        assert!(peak.is_finite() && peak < 10.0);
+    }
+
+    #[test]
+    fn keep_output_finite_when_saturating_at_zero() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter
+            .set_bandform(BandPass)
+            .set_frequency(100.0)
+            .set_q_factor(10.0)
+            .set_limit(Saturate(0.0));
+        let mut signal = [0.0; 1024];
+        signal[512] = 1.0;
+        filter.process(&mut signal);
+        assert!(signal.iter().all(|x| x.is_finite()));
+    }
+
+    #[test]
+    fn fast_sin_approximates_sine() {
+        for i in 0..=100 {
+            let x = i as f32 / 100.0 * PI / 2.0;
+            assert_relative_eq!(fast_sin(x), libm::sinf(x), epsilon = 0.0002);
+        }
+    }
+
+    #[test]
+    fn modulated_tick_matches_static_tick() {
+        let mut static_filter = StateVariableFilter::new(1024);
+        static_filter
+            .set_bandform(LowPass)
+            .set_frequency(100.0)
+            .set_q_factor(2.0);
+        let mut modulated_filter = StateVariableFilter::new(1024);
+        modulated_filter.set_bandform(LowPass);
+
+        let mut rng = rand::thread_rng();
+        for _ in 0..1024 {
+            let x = rng.gen_range(-1.0..=1.0);
+            assert_relative_eq!(
+                static_filter.tick(x),
+                modulated_filter.tick_modulated(x, 100.0, 2.0),
+                epsilon = 0.001
+            );
+        }
+    }
+
+    #[test]
+    fn low_pass_with_modulated_frequency() {
+        use crate::signal::{constant, from_iter};
+        const SAMPLE_RATE: u32 = 1024;
+
+        let mut signal = [0.0; 1024];
+        let mut rng = rand::thread_rng();
+        signal
+            .iter_mut()
+            .for_each(|x| *x = rng.gen_range(-1.0..=1.0));
+
+        let frequency = from_iter((0..1024).map(|i| 50.0 + (i % 2) as f32 * 100.0));
+        let mut filter = StateVariableFilter::new(SAMPLE_RATE);
+        filter.set_bandform(LowPass);
+        filter.process_modulated(&mut signal, frequency, constant(0.7));
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        let low_mean_magnitude = analysis.mean_magnitude(0.0, 100.0);
+        let high_mean_magnitude = analysis.mean_magnitude(150.0, 600.0);
+
+        assert!(low_mean_magnitude / high_mean_magnitude > 3.0);
+    }
+
+    #[test]
+    fn apply_frequency_immediately_without_smoothing() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter.set_frequency(100.0);
+        filter.tick(0.0);
+        assert_relative_eq!(filter.f.value(), 2.0 * f32::sin(PI * 100.0 / 1024.0));
+    }
+
+    #[test]
+    fn smoothen_frequency_changes() {
+        let mut filter = StateVariableFilter::new(1024);
+        filter.set_smoothing(0.1).set_frequency(100.0);
+        let target = 2.0 * f32::sin(PI * 100.0 / 1024.0);
+
+        filter.tick(0.0);
+        assert!(filter.f.value() < target * 0.1);
+
+        for _ in 0..1024 {
+            filter.tick(0.0);
+        }
+        assert_relative_eq!(filter.f.value(), target, epsilon = 0.001);
+    }
+
+    #[test]
+    fn start_smoothing_from_configured_values() {
+        let input = noise();
+        let filter = || {
+            let mut filter = StateVariableFilter::new(1024);
+            filter
+                .set_bandform(LowPass)
+                .set_frequency(100.0)
+                .set_q_factor(5.0);
+            filter
+        };
+
+        let mut expected = input;
+        filter().process(&mut expected);
+
+        let mut smoothed = input;
+        filter().set_smoothing(0.1).process(&mut smoothed);
+
+        for (x, y) in smoothed.iter().zip(expected.iter()) {
+            assert_relative_eq!(x, y);
+        }
+    }
 }
@@ -0,0 +1,108 @@
+use super::StereoSignal;
+use crate::signal::Signal;
+
+/// Turn stereo signal into mono.
+pub trait StereoSignalChannel: StereoSignal {
+    /// Converts the stereo signal into a mono signal of its left channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal};
+    /// use sirena::stereo_signal::{self, StereoSignalChannel};
+    /// let stereo = stereo_signal::merge(signal::constant(1.0), signal::constant(2.0));
+    /// let mut signal = stereo.left();
+    /// assert_eq!(signal.next(), 1.0);
+    /// ```
+    fn left(self) -> Channel<Self>
+    where
+        Self: Sized,
+    {
+        Channel {
+            signal: self,
+            index: 0,
+        }
+    }
+
+    /// Converts the stereo signal into a mono signal of its right channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal};
+    /// use sirena::stereo_signal::{self, StereoSignalChannel};
+    /// let stereo = stereo_signal::merge(signal::constant(1.0), signal::constant(2.0));
+    /// let mut signal = stereo.right();
+    /// assert_eq!(signal.next(), 2.0);
+    /// ```
+    fn right(self) -> Channel<Self>
+    where
+        Self: Sized,
+    {
+        Channel {
+            signal: self,
+            index: 1,
+        }
+    }
+
+    /// Converts the stereo signal into a mono signal by averaging both
+    /// channels.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal::{self, Signal};
+    /// use sirena::stereo_signal::{self, StereoSignalChannel};
+    /// let stereo = stereo_signal::merge(signal::constant(1.0), signal::constant(2.0));
+    /// let mut signal = stereo.mix_down();
+    /// assert_eq!(signal.next(), 1.5);
+    /// ```
+    fn mix_down(self) -> MixDown<Self>
+    where
+        Self: Sized,
+    {
+        MixDown { signal: self }
+    }
+}
+
+impl<T> StereoSignalChannel for T where T: StereoSignal {}
+
+/// Yields a single channel of the stereo `signal`.
+#[derive(Clone)]
+pub struct Channel<S>
+where
+    S: StereoSignal,
+{
+    signal: S,
+    index: usize,
+}
+
+impl<S> Signal for Channel<S>
+where
+    S: StereoSignal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        self.signal.next()[self.index]
+    }
+}
+
+/// Yields average of both channels of the stereo `signal`.
+#[derive(Clone)]
+pub struct MixDown<S>
+where
+    S: StereoSignal,
+{
+    signal: S,
+}
+
+impl<S> Signal for MixDown<S>
+where
+    S: StereoSignal,
+{
+    #[inline]
+    fn next(&mut self) -> f32 {
+        let [left, right] = self.signal.next();
+        (left + right) * 0.5
+    }
+}
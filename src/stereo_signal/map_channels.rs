@@ -0,0 +1,102 @@
+use super::{StereoFrame, StereoSignal};
+
+/// Process each channel of stereo signal separately.
+pub trait StereoSignalMapChannels: StereoSignal {
+    /// Maps left and right samples of each frame through their own closure.
+    ///
+    /// Both closures are called once per frame, so stateful processors, such
+    /// as filters, stay sample-aligned across channels.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal;
+    /// use sirena::one_pole::LowPass;
+    /// use sirena::stereo_signal::{self, StereoSignal, StereoSignalMapChannels};
+    /// let mut left_filter = LowPass::new(48000);
+    /// left_filter.set_frequency(1000.0);
+    /// let mut right_filter = LowPass::new(48000);
+    /// right_filter.set_frequency(2000.0);
+    /// let stereo = stereo_signal::merge(signal::constant(1.0), signal::constant(1.0));
+    /// let mut signal = stereo.map_channels(
+    ///     move |x| left_filter.tick(x),
+    ///     move |x| right_filter.tick(x),
+    /// );
+    /// let [left, right] = signal.next();
+    /// assert!(left < right);
+    /// ```
+    fn map_channels<L, R>(self, left: L, right: R) -> MapChannels<Self, L, R>
+    where
+        Self: Sized,
+        L: FnMut(f32) -> f32,
+        R: FnMut(f32) -> f32,
+    {
+        MapChannels {
+            signal: self,
+            left,
+            right,
+        }
+    }
+}
+
+impl<T> StereoSignalMapChannels for T where T: StereoSignal {}
+
+/// Maps left and right samples yielded by `signal` through the `left` and
+/// `right` closures respectively.
+#[derive(Clone)]
+pub struct MapChannels<S, L, R>
+where
+    S: StereoSignal,
+    L: FnMut(f32) -> f32,
+    R: FnMut(f32) -> f32,
+{
+    signal: S,
+    left: L,
+    right: R,
+}
+
+impl<S, L, R> StereoSignal for MapChannels<S, L, R>
+where
+    S: StereoSignal,
+    L: FnMut(f32) -> f32,
+    R: FnMut(f32) -> f32,
+{
+    #[inline]
+    fn next(&mut self) -> StereoFrame {
+        let [left, right] = self.signal.next();
+        [(self.left)(left), (self.right)(right)]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal;
+    use crate::stereo_signal::merge;
+
+    #[test]
+    fn keep_channels_sample_aligned() {
+        let ramp = [0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0];
+
+        let mut left_previous = 0.0;
+        let mut right_sum = 0.0;
+        let mut signal = merge(signal::from_iter(ramp), signal::from_iter(ramp)).map_channels(
+            move |x| {
+                let y = left_previous;
+                left_previous = x;
+                y
+            },
+            move |x| {
+                right_sum += x;
+                right_sum
+            },
+        );
+
+        let mut expected_sum = 0.0;
+        for (i, x) in ramp.iter().enumerate() {
+            expected_sum += x;
+            let expected_previous = if i > 0 { ramp[i - 1] } else { 0.0 };
+            assert_eq!(signal.next(), [expected_previous, expected_sum]);
+        }
+    }
+}
@@ -0,0 +1,46 @@
+//! Merge two mono signals into a stereo signal.
+
+use super::{StereoFrame, StereoSignal};
+use crate::signal::Signal;
+
+/// Create a new `StereoSignal` with channels taken from given mono signals.
+///
+/// # Example
+///
+/// ```rust
+/// use sirena::signal;
+/// use sirena::stereo_signal::{self, StereoSignal};
+/// let left = signal::constant(1.0);
+/// let right = signal::constant(-1.0);
+/// let mut signal = stereo_signal::merge(left, right);
+/// assert_eq!(signal.next(), [1.0, -1.0]);
+/// ```
+pub fn merge<L, R>(left: L, right: R) -> Merge<L, R>
+where
+    L: Signal,
+    R: Signal,
+{
+    Merge { left, right }
+}
+
+/// A stereo signal composed of two mono signals.
+#[derive(Clone)]
+pub struct Merge<L, R>
+where
+    L: Signal,
+    R: Signal,
+{
+    left: L,
+    right: R,
+}
+
+impl<L, R> StereoSignal for Merge<L, R>
+where
+    L: Signal,
+    R: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> StereoFrame {
+        [self.left.next(), self.right.next()]
+    }
+}
@@ -0,0 +1,84 @@
+use super::{StereoFrame, StereoSignal};
+
+/// Convert stereo signal between left/right and mid/side representation.
+pub trait StereoSignalMidSide: StereoSignal {
+    /// Encodes left/right stereo signal into mid/side, yielding mid in the
+    /// first and side in the second channel.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal;
+    /// use sirena::stereo_signal::{self, StereoSignal, StereoSignalMidSide};
+    /// let stereo = stereo_signal::merge(signal::constant(1.0), signal::constant(0.5));
+    /// let mut signal = stereo.encode_mid_side();
+    /// assert_eq!(signal.next(), [0.75, 0.25]);
+    /// ```
+    fn encode_mid_side(self) -> EncodeMidSide<Self>
+    where
+        Self: Sized,
+    {
+        EncodeMidSide { signal: self }
+    }
+
+    /// Decodes mid/side stereo signal, with mid in the first and side in the
+    /// second channel, back into left/right.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal;
+    /// use sirena::stereo_signal::{self, StereoSignal, StereoSignalMidSide};
+    /// let stereo = stereo_signal::merge(signal::constant(1.0), signal::constant(0.5));
+    /// let mut signal = stereo.encode_mid_side().decode_mid_side();
+    /// assert_eq!(signal.next(), [1.0, 0.5]);
+    /// ```
+    fn decode_mid_side(self) -> DecodeMidSide<Self>
+    where
+        Self: Sized,
+    {
+        DecodeMidSide { signal: self }
+    }
+}
+
+impl<T> StereoSignalMidSide for T where T: StereoSignal {}
+
+/// Converts left/right frames yielded by `signal` into mid/side.
+#[derive(Clone)]
+pub struct EncodeMidSide<S>
+where
+    S: StereoSignal,
+{
+    signal: S,
+}
+
+impl<S> StereoSignal for EncodeMidSide<S>
+where
+    S: StereoSignal,
+{
+    #[inline]
+    fn next(&mut self) -> StereoFrame {
+        let [left, right] = self.signal.next();
+        [(left + right) * 0.5, (left - right) * 0.5]
+    }
+}
+
+/// Converts mid/side frames yielded by `signal` into left/right.
+#[derive(Clone)]
+pub struct DecodeMidSide<S>
+where
+    S: StereoSignal,
+{
+    signal: S,
+}
+
+impl<S> StereoSignal for DecodeMidSide<S>
+where
+    S: StereoSignal,
+{
+    #[inline]
+    fn next(&mut self) -> StereoFrame {
+        let [mid, side] = self.signal.next();
+        [mid + side, mid - side]
+    }
+}
@@ -0,0 +1,25 @@
+//! Use the StereoSignal trait to abstract two-channel signal as
+//! infinite-iterator-like objects.
+//!
+//! This is a stereo counterpart of the [`signal`](crate::signal) module.
+//! Mono signals can be merged or panned into stereo, processed with
+//! stereo-aware combinators such as mid/side conversion, and split back into
+//! mono signals, keeping both channels in lockstep.
+
+mod channel;
+mod map_channels;
+mod merge;
+mod mid_side;
+mod pan;
+mod stereo_signal_trait;
+mod take;
+
+pub use channel::StereoSignalChannel;
+pub use map_channels::StereoSignalMapChannels;
+pub use merge::merge;
+pub use mid_side::StereoSignalMidSide;
+pub use pan::SignalPan;
+pub use stereo_signal_trait::{StereoFrame, StereoSignal};
+pub use take::StereoSignalTake;
+
+pub const EQUILIBRIUM: StereoFrame = [0.0, 0.0];
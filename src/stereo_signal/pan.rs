@@ -0,0 +1,74 @@
+use super::{StereoFrame, StereoSignal};
+use crate::signal::Signal;
+
+/// Pan mono signal into stereo.
+pub trait SignalPan: Signal {
+    /// Places the mono signal into stereo field, reading the position from
+    /// the given signal on each tick.
+    ///
+    /// Position of `-1.0` is fully left, `0.0` center and `1.0` fully right.
+    /// It gets clamped between these. Constant power pan law is used, so the
+    /// perceived loudness stays the same across the stereo field.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # #[macro_use]
+    /// # extern crate approx;
+    /// # fn main() {
+    /// use sirena::signal::{self, Signal};
+    /// use sirena::stereo_signal::{SignalPan, StereoSignal};
+    /// let position = signal::from_iter([-1.0, 0.0, 1.0]);
+    /// let mut signal = signal::constant(1.0).pan(position);
+    ///
+    /// let [left, right] = signal.next();
+    /// assert_relative_eq!(left, 1.0);
+    /// assert_relative_eq!(right, 0.0);
+    ///
+    /// let [left, right] = signal.next();
+    /// assert_relative_eq!(left, core::f32::consts::FRAC_1_SQRT_2);
+    /// assert_relative_eq!(right, core::f32::consts::FRAC_1_SQRT_2);
+    ///
+    /// let [left, right] = signal.next();
+    /// assert_relative_eq!(left, 0.0, epsilon = 0.0001);
+    /// assert_relative_eq!(right, 1.0);
+    /// # }
+    /// ```
+    fn pan<P>(self, position: P) -> Pan<Self, P>
+    where
+        Self: Sized,
+        P: Signal,
+    {
+        Pan {
+            signal: self,
+            position,
+        }
+    }
+}
+
+impl<T> SignalPan for T where T: Signal {}
+
+/// Pans samples yielded by `signal` based on samples of `position`.
+#[derive(Clone)]
+pub struct Pan<S, P>
+where
+    S: Signal,
+    P: Signal,
+{
+    signal: S,
+    position: P,
+}
+
+impl<S, P> StereoSignal for Pan<S, P>
+where
+    S: Signal,
+    P: Signal,
+{
+    #[inline]
+    fn next(&mut self) -> StereoFrame {
+        const FRAC_PI_4: f32 = core::f32::consts::FRAC_PI_4;
+        let sample = self.signal.next();
+        let angle = (self.position.next().clamp(-1.0, 1.0) + 1.0) * FRAC_PI_4;
+        [sample * libm::cosf(angle), sample * libm::sinf(angle)]
+    }
+}
@@ -0,0 +1,41 @@
+/// A frame of stereo signal, holding left and right samples respectively.
+pub type StereoFrame = [f32; 2];
+
+/// Types that yield frames of a two-channel PCM signal.
+pub trait StereoSignal {
+    /// Read the next frame of given signal.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal;
+    /// use sirena::stereo_signal::{self, StereoSignal};
+    /// let left = signal::from_iter([0.1, 0.2]);
+    /// let right = signal::from_iter([0.3, 0.4]);
+    /// let mut signal = stereo_signal::merge(left, right);
+    /// assert_eq!(signal.next(), [0.1, 0.3]);
+    /// assert_eq!(signal.next(), [0.2, 0.4]);
+    /// ```
+    fn next(&mut self) -> StereoFrame;
+
+    /// Borrows a StereoSignal rather than consuming it.
+    ///
+    /// This is useful to allow applying signal adaptors while still retaining
+    /// ownership of the original signal.
+    fn by_ref(&mut self) -> &mut Self
+    where
+        Self: Sized,
+    {
+        self
+    }
+}
+
+impl<S> StereoSignal for &mut S
+where
+    S: StereoSignal + ?Sized,
+{
+    #[inline]
+    fn next(&mut self) -> StereoFrame {
+        (**self).next()
+    }
+}
@@ -0,0 +1,53 @@
+use super::{StereoFrame, StereoSignal};
+
+/// Take `n` frames of a stereo signal.
+pub trait StereoSignalTake: StereoSignal {
+    /// Converts the `StereoSignal` into an `Iterator` that will yield the
+    /// given number frames before returning `None`.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use sirena::signal;
+    /// use sirena::stereo_signal::{self, StereoSignalTake};
+    /// let left = signal::from_iter([0.1, 0.2]);
+    /// let right = signal::from_iter([0.3, 0.4]);
+    /// let mut signal = stereo_signal::merge(left, right).take(1);
+    /// assert_eq!(signal.next(), Some([0.1, 0.3]));
+    /// assert_eq!(signal.next(), None);
+    /// ```
+    fn take(self, n: usize) -> Take<Self>
+    where
+        Self: Sized,
+    {
+        Take { signal: self, n }
+    }
+}
+
+impl<T> StereoSignalTake for T where T: StereoSignal {}
+
+/// An iterator that yields `n` number of frames from the inner `signal`.
+#[derive(Clone)]
+pub struct Take<S>
+where
+    S: StereoSignal,
+{
+    signal: S,
+    n: usize,
+}
+
+impl<S> Iterator for Take<S>
+where
+    S: StereoSignal,
+{
+    type Item = StereoFrame;
+
+    #[inline]
+    fn next(&mut self) -> Option<StereoFrame> {
+        if self.n == 0 {
+            return None;
+        }
+        self.n -= 1;
+        Some(self.signal.next())
+    }
+}
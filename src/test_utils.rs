@@ -0,0 +1,31 @@
+//! Helpers shared by tests of filters and effects.
+
+use crate::spectral_analysis::SpectralAnalysis;
+use rand::Rng;
+
+const SAMPLE_RATE: u32 = 1024;
+
+/// Generate a second of white noise sampled at 1024 hz.
+pub fn noise() -> [f32; SAMPLE_RATE as usize] {
+    let mut signal = [0.0; SAMPLE_RATE as usize];
+    let mut rng = rand::thread_rng();
+    signal
+        .iter_mut()
+        .for_each(|x| *x = rng.gen_range(-1.0..=1.0));
+    signal
+}
+
+/// Generate a second long unit impulse sampled at 1024 hz.
+pub fn impulse() -> [f32; SAMPLE_RATE as usize] {
+    let mut signal = [0.0; SAMPLE_RATE as usize];
+    signal[0] = 1.0;
+    signal
+}
+
+/// Analyze the response of the given processor to an impulse, the resulting
+/// magnitudes are equal to squared gain on given frequencies.
+pub fn impulse_response(process: impl FnOnce(&mut [f32])) -> SpectralAnalysis {
+    let mut signal = impulse();
+    process(&mut signal);
+    SpectralAnalysis::analyze(&signal, SAMPLE_RATE)
+}
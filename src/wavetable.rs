@@ -0,0 +1,210 @@
+//! Wavetable oscillator reading from band-limited tables.
+//!
+//! The oscillator reads from a bank of user-supplied tables, for example
+//! stored in a slice allocated through
+//! [`MemoryManager`](crate::memory_manager::MemoryManager). The bank contains
+//! multiple waves to morph between, each of them stored in multiple
+//! mip-levels with decreasing number of harmonics. The oscillator picks the
+//! level according to the played frequency to prevent aliasing.
+//!
+//! The bank must be laid out as `[wave][level][sample]`. With table length
+//! `L`, level `0` can contain harmonics up to `L / 2`, level `1` up to
+//! `L / 4` and so on, each level halving the number of harmonics.
+
+#[allow(unused_imports)]
+use micromath::F32Ext;
+
+use crate::signal::Signal;
+
+/// Yields wave read from the wavetable bank.
+#[derive(Debug)]
+pub struct Wavetable<'a> {
+    bank: &'a [f32],
+    table_length: usize,
+    levels: usize,
+    waves: usize,
+    sample_rate: u32,
+    phase: f32,
+    step: f32,
+    level: usize,
+    morph: f32,
+}
+
+impl<'a> Wavetable<'a> {
+    /// Initialize the oscillator over given bank of tables.
+    ///
+    /// # Panics
+    ///
+    /// The length of the bank must be a non-zero multiple of `table_length *
+    /// levels`, otherwise the function panics.
+    pub fn new(bank: &'a [f32], table_length: usize, levels: usize, sample_rate: u32) -> Self {
+        assert!(table_length > 0 && levels > 0);
+        assert!(!bank.is_empty() && bank.len().is_multiple_of(table_length * levels));
+
+        let mut wavetable = Self {
+            bank,
+            table_length,
+            levels,
+            waves: bank.len() / (table_length * levels),
+            sample_rate,
+            phase: 0.0,
+            step: 0.0,
+            level: 0,
+            morph: 0.0,
+        };
+        wavetable.set_frequency(0.0);
+        wavetable
+    }
+
+    /// Set frequency in hz. It also selects the table level, so higher
+    /// frequencies play waves with fewer harmonics and avoid aliasing.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        self.step = frequency / self.sample_rate as f32;
+        self.level = self.select_level();
+        self
+    }
+
+    /// Set position between waves of the bank.
+    ///
+    /// Morph `0.0` plays the first wave, `1.0` plays the last. Positions in
+    /// between crossfade adjacent waves.
+    pub fn set_morph(&mut self, morph: f32) -> &mut Self {
+        self.morph = morph.clamp(0.0, 1.0);
+        self
+    }
+
+    // Pick the first level whose highest harmonic stays below the Nyquist
+    // frequency. Level `l` holds harmonics up to `L / 2^(l + 1)` while there
+    // is `1 / (2 * step)` harmonics available.
+    fn select_level(&self) -> usize {
+        let ratio = f32::abs(self.step) * self.table_length as f32;
+        if ratio <= 1.0 {
+            return 0;
+        }
+        let level = libm::ceilf(libm::log2f(ratio)) as usize;
+        usize::min(level, self.levels - 1)
+    }
+
+    fn read(&self, wave: usize, phase: f32) -> f32 {
+        let offset = (wave * self.levels + self.level) * self.table_length;
+        let table = &self.bank[offset..offset + self.table_length];
+
+        let position = phase * self.table_length as f32;
+        let index_a = position as usize % self.table_length;
+        let index_b = (index_a + 1) % self.table_length;
+
+        let a = table[index_a];
+        let b = table[index_b];
+        a + (b - a) * position.fract()
+    }
+}
+
+impl<'a> Signal for Wavetable<'a> {
+    fn next(&mut self) -> f32 {
+        let position = self.morph * (self.waves - 1) as f32;
+        let wave_a = position as usize;
+        let wave_b = usize::min(wave_a + 1, self.waves - 1);
+        let mix = position - wave_a as f32;
+
+        let a = self.read(wave_a, self.phase);
+        let sample = if mix > 0.0 {
+            let b = self.read(wave_b, self.phase);
+            a + (b - a) * mix
+        } else {
+            a
+        };
+
+        self.phase += self.step;
+        self.phase -= libm::floorf(self.phase);
+
+        sample
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::test_utils::harmonic_to_alias_ratio;
+    use crate::spectral_analysis::SpectralAnalysis;
+    use core::f32::consts::PI;
+
+    const TABLE_LENGTH: usize = 256;
+    const LEVELS: usize = 8;
+    const WAVES: usize = 2;
+    const BANK_LENGTH: usize = TABLE_LENGTH * LEVELS * WAVES;
+
+    // Bank with sine as the first and sawtooth as the second wave.
+    fn sine_saw_bank() -> [f32; BANK_LENGTH] {
+        let mut bank = [0.0; BANK_LENGTH];
+        for level in 0..LEVELS {
+            let harmonics = (TABLE_LENGTH / 2) >> level;
+            for i in 0..TABLE_LENGTH {
+                let phase = i as f32 / TABLE_LENGTH as f32;
+                bank[level * TABLE_LENGTH + i] = f32::sin(2.0 * PI * phase);
+                bank[(LEVELS + level) * TABLE_LENGTH + i] = (1..=harmonics).fold(0.0, |sum, k| {
+                    sum - 2.0 / PI * f32::sin(2.0 * PI * phase * k as f32) / k as f32
+                });
+            }
+        }
+        bank
+    }
+
+    #[test]
+    #[should_panic]
+    fn panic_on_bank_with_invalid_length() {
+        let bank = [0.0; 10];
+        let _wavetable = Wavetable::new(&bank, 4, 2, 1024);
+    }
+
+    #[test]
+    fn play_requested_frequency() {
+        const SAMPLE_RATE: u32 = 1024;
+        let bank = sine_saw_bank();
+        let mut wavetable = Wavetable::new(&bank, TABLE_LENGTH, LEVELS, SAMPLE_RATE);
+        wavetable.set_frequency(100.0);
+
+        let mut signal = [0.0; 1024];
+        signal.iter_mut().for_each(|x| *x = wavetable.next());
+
+        let analysis = SpectralAnalysis::analyze(&signal, SAMPLE_RATE);
+        assert_relative_eq!(analysis.strongest_peak(), 100.0, epsilon = 1.0);
+    }
+
+    #[test]
+    fn select_level_by_frequency() {
+        let bank = sine_saw_bank();
+        let mut wavetable = Wavetable::new(&bank, TABLE_LENGTH, LEVELS, 1024);
+
+        wavetable.set_frequency(1.0);
+        assert_eq!(wavetable.level, 0);
+
+        wavetable.set_frequency(5.0);
+        assert_eq!(wavetable.level, 1);
+
+        wavetable.set_frequency(300.0);
+        assert_eq!(wavetable.level, LEVELS - 1);
+    }
+
+    #[test]
+    fn suppress_aliasing_of_high_pitch() {
+        let bank = sine_saw_bank();
+        let mut wavetable = Wavetable::new(&bank, TABLE_LENGTH, LEVELS, 1024);
+        wavetable.set_frequency(50.0).set_morph(1.0);
+
+        assert!(harmonic_to_alias_ratio(&mut wavetable, 50.0) > 1000.0);
+    }
+
+    #[test]
+    fn crossfade_between_waves() {
+        let bank = sine_saw_bank();
+        let mut wavetable = Wavetable::new(&bank, TABLE_LENGTH, LEVELS, 1024);
+        wavetable.set_frequency(4.0);
+
+        let sine = bank[64];
+        let saw = bank[LEVELS * TABLE_LENGTH + 64];
+
+        wavetable.phase = 0.25;
+        wavetable.set_morph(0.5);
+        assert_relative_eq!(wavetable.next(), (sine + saw) / 2.0, epsilon = 0.001);
+    }
+}
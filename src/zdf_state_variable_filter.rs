@@ -0,0 +1,187 @@
+//! Zero-delay-feedback state variable filter can be used as low/high/band
+//! pass or band reject.
+//!
+//! Unlike [`StateVariableFilter`](crate::state_variable_filter::StateVariableFilter),
+//! which uses the Chamberlin topology, this filter uses trapezoidal
+//! integration. It stays stable and accurately tuned all the way up to the
+//! Nyquist frequency.
+
+use core::f32::consts::PI;
+
+pub use crate::state_variable_filter::Bandform::{self, *};
+
+/// Yields filtered signal.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[derive(Debug)]
+pub struct ZdfStateVariableFilter {
+    sample_rate: u32,
+    bandform: Bandform,
+    g: f32,
+    k: f32,
+    a1: f32,
+    a2: f32,
+    a3: f32,
+    ic1eq: f32,
+    ic2eq: f32,
+}
+
+impl ZdfStateVariableFilter {
+    pub fn new(sample_rate: u32) -> Self {
+        let mut filter = Self {
+            sample_rate,
+            bandform: BandPass,
+            g: 0.0,
+            k: 0.0,
+            a1: 0.0,
+            a2: 0.0,
+            a3: 0.0,
+            ic1eq: 0.0,
+            ic2eq: 0.0,
+        };
+        filter.set_q_factor(0.7);
+        filter.set_frequency(0.0);
+        filter
+    }
+
+    pub fn set_bandform(&mut self, bandform: Bandform) -> &mut Self {
+        self.bandform = bandform;
+        self
+    }
+
+    /// Set the cutoff frequency.
+    ///
+    /// It gets clamped just below the Nyquist frequency.
+    pub fn set_frequency(&mut self, frequency: f32) -> &mut Self {
+        let nyquist = self.sample_rate as f32 / 2.0;
+        let frequency = frequency.clamp(0.0, nyquist * 0.999);
+        self.g = libm::tanf(PI * frequency / self.sample_rate as f32);
+        self.update_coefficients();
+        self
+    }
+
+    pub fn set_q_factor(&mut self, q_factor: f32) -> &mut Self {
+        self.k = 1.0 / f32::max(q_factor, 0.01);
+        self.update_coefficients();
+        self
+    }
+
+    pub fn process(&mut self, signal: &mut [f32]) {
+        for x in signal.iter_mut() {
+            *x = self.tick(*x);
+        }
+    }
+
+    pub fn pass(&mut self, signal: &[f32]) {
+        for x in signal.iter() {
+            self.tick(*x);
+        }
+    }
+
+    // https://cytomic.com/files/dsp/SvfLinearTrapOptimised2.pdf
+    pub fn tick(&mut self, value: f32) -> f32 {
+        let v3 = value - self.ic2eq;
+        let v1 = self.a1 * self.ic1eq + self.a2 * v3;
+        let v2 = self.ic2eq + self.a2 * self.ic1eq + self.a3 * v3;
+        self.ic1eq = 2.0 * v1 - self.ic1eq;
+        self.ic2eq = 2.0 * v2 - self.ic2eq;
+
+        let low = v2;
+        let band = v1;
+        match self.bandform {
+            LowPass => low,
+            HighPass => value - self.k * band - low,
+            BandPass => band,
+            BandReject => value - self.k * band,
+        }
+    }
+
+    fn update_coefficients(&mut self) {
+        self.a1 = 1.0 / (1.0 + self.g * (self.g + self.k));
+        self.a2 = self.g * self.a1;
+        self.a3 = self.g * self.a2;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{impulse_response, noise};
+    use core::f32::consts::FRAC_1_SQRT_2;
+
+    const SAMPLE_RATE: u32 = 1024;
+
+    #[test]
+    fn initialize_filter() {
+        const SAMPLE_RATE: u32 = 1;
+        let _filter = ZdfStateVariableFilter::new(SAMPLE_RATE);
+    }
+
+    #[test]
+    fn low_pass_attenuates_by_3_db_on_cutoff_across_whole_range() {
+        for frequency in [20.0, 50.0, 100.0, 200.0, 300.0, 400.0, 450.0, 500.0] {
+            let mut filter = ZdfStateVariableFilter::new(SAMPLE_RATE);
+            filter
+                .set_bandform(LowPass)
+                .set_frequency(frequency)
+                .set_q_factor(FRAC_1_SQRT_2);
+            let analysis = impulse_response(|signal| filter.process(signal));
+
+            assert_relative_eq!(analysis.magnitude(0.0), 1.0, epsilon = 0.01);
+            assert_relative_eq!(analysis.magnitude(frequency), 0.5, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn high_pass_attenuates_by_3_db_on_cutoff_across_whole_range() {
+        for frequency in [20.0, 50.0, 100.0, 200.0, 300.0, 400.0, 450.0, 500.0] {
+            let mut filter = ZdfStateVariableFilter::new(SAMPLE_RATE);
+            filter
+                .set_bandform(HighPass)
+                .set_frequency(frequency)
+                .set_q_factor(FRAC_1_SQRT_2);
+            let analysis = impulse_response(|signal| filter.process(signal));
+
+            assert_relative_eq!(analysis.magnitude(0.0), 0.0, epsilon = 0.01);
+            assert_relative_eq!(analysis.magnitude(frequency), 0.5, epsilon = 0.01);
+        }
+    }
+
+    #[test]
+    fn band_pass_peaks_on_center_frequency() {
+        let mut filter = ZdfStateVariableFilter::new(SAMPLE_RATE);
+        filter
+            .set_bandform(BandPass)
+            .set_frequency(300.0)
+            .set_q_factor(10.0);
+        let analysis = impulse_response(|signal| filter.process(signal));
+
+        assert_relative_eq!(analysis.strongest_peak(), 300.0, epsilon = 1.0);
+    }
+
+    #[test]
+    fn band_reject_removes_center_frequency() {
+        let mut filter = ZdfStateVariableFilter::new(SAMPLE_RATE);
+        filter
+            .set_bandform(BandReject)
+            .set_frequency(300.0)
+            .set_q_factor(1.0);
+        let analysis = impulse_response(|signal| filter.process(signal));
+
+        assert!(analysis.magnitude(300.0) < 0.01);
+        assert_relative_eq!(analysis.magnitude(50.0), 1.0, epsilon = 0.05);
+    }
+
+    #[test]
+    fn stay_stable_near_nyquist_with_high_resonance() {
+        let mut signal = noise();
+
+        let mut filter = ZdfStateVariableFilter::new(SAMPLE_RATE);
+        filter
+            .set_bandform(LowPass)
+            .set_frequency(510.0)
+            .set_q_factor(20.0);
+        filter.process(&mut signal);
+
+        assert!(signal.iter().all(|x| x.is_finite() && f32::abs(*x) < 100.0));
+    }
+}